@@ -11,6 +11,8 @@ use sp_runtime::traits::{IdentifyAccount, Verify};
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 const DEFAULT_PROTOCOL_ID: &str = "vaccine-chain";
+// Role ID of the system manager bound to Alice at genesis.
+const GENESIS_SYSTEM_MANAGER: &str = "00000000-0000-4000-8000-000000000000";
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
@@ -37,6 +39,11 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Role ID of the genesis system manager.
+pub fn system_manager_id() -> UserId {
+	UserId::try_from(GENESIS_SYSTEM_MANAGER).expect("the genesis system manager ID is a valid UUID")
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -65,7 +72,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
 				],
 				true,
-				vec!["0".as_bytes().to_vec()],
+				vec![(system_manager_id(), get_account_id_from_seed::<sr25519::Public>("Alice"))],
 			)
 		},
 		// Bootnodes
@@ -114,7 +121,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
 				true,
-				vec!["0".as_bytes().to_vec()],
+				vec![(system_manager_id(), get_account_id_from_seed::<sr25519::Public>("Alice"))],
 			)
 		},
 		// Bootnodes
@@ -138,7 +145,7 @@ fn testnet_genesis(
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
 	_enable_println: bool,
-	system_account: Vec<Vec<u8>>,
+	system_account: Vec<(UserId, AccountId)>,
 ) -> GenesisConfig {
 	GenesisConfig {
 		system: SystemConfig {
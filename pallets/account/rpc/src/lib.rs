@@ -0,0 +1,169 @@
+//! RPC resolving `did:vaccine:<RoleId>` identifiers to W3C DID documents, so that partners can
+//! verify the signatures of manufacturers and authorities with standard DID tooling.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::{Codec, Encode};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_account::{DidResolution, KeyType, RoleId, ACCOUNT_KEY_ID};
+pub use pallet_account_runtime_api::AccountDidApi as AccountDidRuntimeApi;
+use serde_json::{json, Value};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[cfg(test)]
+mod tests;
+
+/// Prefix of the DIDs of role IDs.
+pub const DID_PREFIX: &str = "did:vaccine:";
+
+#[rpc(client, server)]
+pub trait DidApi<BlockHash> {
+	/// Resolve a `did:vaccine:<RoleId>` to its DID document, `null` when no key is bound to the
+	/// role ID.
+	#[method(name = "did_resolve")]
+	fn resolve(&self, did: String, at: Option<BlockHash>) -> RpcResult<Option<Value>>;
+}
+
+/// Error codes of the DID RPC.
+pub enum Error {
+	/// The identifier is not a `did:vaccine:<RoleId>`.
+	InvalidDid,
+	/// The call into the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::InvalidDid => 1,
+			Error::RuntimeError => 2,
+		}
+	}
+}
+
+/// Resolves DIDs from the state of the given block.
+pub struct Did<C, Block, AccountId> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, AccountId)>,
+}
+
+impl<C, Block, AccountId> Did<C, Block, AccountId> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+#[async_trait]
+impl<C, Block, AccountId> DidApiServer<<Block as BlockT>::Hash> for Did<C, Block, AccountId>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AccountDidRuntimeApi<Block, AccountId>,
+	AccountId: Codec + Send + Sync + 'static,
+{
+	fn resolve(&self, did: String, at: Option<Block::Hash>) -> RpcResult<Option<Value>> {
+		let role_id = did
+			.strip_prefix(DID_PREFIX)
+			.and_then(|id| RoleId::try_from(id).ok())
+			.ok_or_else(|| {
+				CallError::Custom(ErrorObject::owned(
+					Error::InvalidDid.into(),
+					"Not a did:vaccine identifier.",
+					Some(did.clone()),
+				))
+			})?;
+
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let resolution = api.resolve_did(&at, role_id.clone()).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to resolve the DID.",
+				Some(e.to_string()),
+			))
+		})?;
+
+		Ok(resolution.map(|resolution| did_document(&role_id, resolution)))
+	}
+}
+
+/// W3C DID document of `role_id`, listing the key bound to it as the `#account` key.
+pub fn did_document<AccountId: Encode>(
+	role_id: &RoleId,
+	resolution: DidResolution<AccountId>,
+) -> Value {
+	let did = did_of(role_id);
+	let url = |fragment: &[u8]| format!("{}#{}", did, String::from_utf8_lossy(fragment));
+	let (controller, keys, services) = match resolution.document {
+		Some(document) => (document.controller, document.keys, document.services),
+		None => Default::default(),
+	};
+
+	// keys of the node are sr25519 keys
+	let mut methods = vec![verification_method(
+		&did,
+		url(ACCOUNT_KEY_ID),
+		KeyType::Sr25519,
+		&resolution.account.encode(),
+	)];
+	methods.extend(
+		keys.iter()
+			.map(|key| verification_method(&did, url(&key.id), key.key_type, &key.public_key)),
+	);
+	let services: Vec<Value> = services
+		.iter()
+		.map(|service| {
+			json!({
+				"id": url(&service.id),
+				"type": String::from_utf8_lossy(&service.service_type),
+				"serviceEndpoint": String::from_utf8_lossy(&service.endpoint),
+			})
+		})
+		.collect();
+	let signing_keys: Vec<Value> = methods.iter().map(|method| method["id"].clone()).collect();
+
+	let mut value = json!({
+		"@context": ["https://www.w3.org/ns/did/v1"],
+		"id": did,
+		"verificationMethod": methods,
+		"authentication": [url(ACCOUNT_KEY_ID)],
+		"assertionMethod": signing_keys,
+		"service": services,
+	});
+	if let Some(controller) = controller {
+		value["controller"] = Value::String(did_of(&controller));
+	}
+	value
+}
+
+fn did_of(role_id: &RoleId) -> String {
+	format!("{}{}", DID_PREFIX, String::from_utf8_lossy(role_id.as_bytes()))
+}
+
+fn verification_method(
+	controller: &str,
+	id: String,
+	key_type: KeyType,
+	public_key: &[u8],
+) -> Value {
+	// type of the key and multicodec prefix of its public key
+	let (method_type, codec) = match key_type {
+		KeyType::Sr25519 => ("Sr25519VerificationKey2020", [0xef, 0x01]),
+		KeyType::Ed25519 => ("Ed25519VerificationKey2020", [0xed, 0x01]),
+		KeyType::Ecdsa => ("EcdsaSecp256k1VerificationKey2019", [0xe7, 0x01]),
+	};
+	let multicodec = [&codec[..], public_key].concat();
+	json!({
+		"id": id,
+		"type": method_type,
+		"controller": controller,
+		// base58btc multibase
+		"publicKeyMultibase": format!("z{}", bs58::encode(multicodec).into_string()),
+	})
+}
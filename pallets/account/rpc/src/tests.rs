@@ -0,0 +1,96 @@
+//! Checks the DID documents built from the resolutions of the runtime.
+
+use crate::{did_document, verification_method};
+use pallet_account::{DidDocument, DidResolution, DidService, KeyType, RoleId, VerificationKey};
+use serde_json::json;
+
+const ROLE_ID: &str = "8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93";
+const CONTROLLER: &str = "0f4e2b7d-91a5-4c3e-8d6f-5b2a9e7c4d10";
+const DID: &str = "did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93";
+
+fn role_id(id: &str) -> RoleId {
+	RoleId::try_from(id).unwrap()
+}
+
+#[test]
+fn should_encode_public_keys_as_multibase() {
+	let method = verification_method(DID, format!("{}#key-1", DID), KeyType::Ed25519, &[0u8; 32]);
+	assert_eq!(
+		method,
+		json!({
+			"id": "did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#key-1",
+			"type": "Ed25519VerificationKey2020",
+			"controller": DID,
+			"publicKeyMultibase": "z6MkeTG3bFFSLYVU7VqhgZxqr6YzpaGrQtFMh1uvqGy1vDnP",
+		})
+	);
+
+	let method = verification_method(DID, format!("{}#key-2", DID), KeyType::Ecdsa, &[2u8; 33]);
+	assert_eq!(method["type"], "EcdsaSecp256k1VerificationKey2019");
+	assert_eq!(method["publicKeyMultibase"], "zQ3shMYdM8Kuh6LHsfSkGi2tUnnX1e4u286ZN1qzm8wcrk3zh");
+}
+
+#[test]
+fn should_list_the_bound_key_without_document() {
+	let resolution = DidResolution { account: [1u8; 32], document: None };
+	assert_eq!(
+		did_document(&role_id(ROLE_ID), resolution),
+		json!({
+			"@context": ["https://www.w3.org/ns/did/v1"],
+			"id": DID,
+			"verificationMethod": [{
+				"id": "did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#account",
+				"type": "Sr25519VerificationKey2020",
+				"controller": DID,
+				"publicKeyMultibase": "z6QNk5M6sRbbR1L9Hp25RiBYByzo1axXWW5asVy5aHeb5F8L",
+			}],
+			"authentication": ["did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#account"],
+			"assertionMethod": ["did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#account"],
+			"service": [],
+		})
+	);
+}
+
+#[test]
+fn should_add_the_keys_services_and_controller_of_the_document() {
+	let document = DidDocument {
+		controller: Some(role_id(CONTROLLER)),
+		keys: vec![VerificationKey {
+			id: b"key-1".to_vec(),
+			key_type: KeyType::Ed25519,
+			public_key: vec![0u8; 32],
+		}],
+		services: vec![DidService {
+			id: b"certificates".to_vec(),
+			service_type: b"LinkedDomains".to_vec(),
+			endpoint: b"https://example.org".to_vec(),
+		}],
+		updated_at: 1_000,
+	};
+	let resolution = DidResolution { account: [1u8; 32], document: Some(document) };
+	let value = did_document(&role_id(ROLE_ID), resolution);
+
+	assert_eq!(value["controller"], "did:vaccine:0f4e2b7d-91a5-4c3e-8d6f-5b2a9e7c4d10");
+	assert_eq!(value["verificationMethod"].as_array().unwrap().len(), 2);
+	assert_eq!(
+		value["verificationMethod"][1]["id"],
+		"did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#key-1"
+	);
+	// the bound key alone authenticates the role ID, every key signs for it
+	assert_eq!(value["authentication"].as_array().unwrap().len(), 1);
+	assert_eq!(
+		value["assertionMethod"],
+		json!([
+			"did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#account",
+			"did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#key-1",
+		])
+	);
+	assert_eq!(
+		value["service"],
+		json!([{
+			"id": "did:vaccine:8a1c6a2e-3c6b-4b8e-9f4a-2d5e7c1b0a93#certificates",
+			"type": "LinkedDomains",
+			"serviceEndpoint": "https://example.org",
+		}])
+	);
+}
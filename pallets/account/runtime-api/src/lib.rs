@@ -0,0 +1,15 @@
+//! Runtime API of the account pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_account::{DidResolution, RoleId};
+
+sp_api::decl_runtime_apis! {
+	pub trait AccountDidApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// What `did:vaccine:<role_id>` resolves to, `None` when no key is bound to the role ID.
+		fn resolve_did(role_id: RoleId) -> Option<DidResolution<AccountId>>;
+	}
+}
@@ -5,16 +5,25 @@
 /// <https://docs.substrate.io/v3/runtime/frame>
 pub use pallet::*;
 
-use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::UnixTime};
+use frame_support::{
+	dispatch::DispatchResult,
+	pallet_prelude::*,
+	traits::{Currency, ReservableCurrency, UnixTime},
+};
 use frame_system::pallet_prelude::*;
 use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Hash, SaturatedConversion},
+	DispatchError,
+};
 
-use sp_std::vec::Vec;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 #[cfg(feature = "std")]
-use serde::{ser::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+	de::Error as _, ser::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer,
+};
 
-// pub type RoleId = [u8; 36];
-pub type RoleId = Vec<u8>;
+pub type RoleId = UserId;
 #[cfg(test)]
 mod mock;
 
@@ -24,10 +33,57 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
-pub trait AccountPallet {
+pub mod migrations;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
+pub trait AccountPallet<AccountId> {
+	fn check_owner(who: &AccountId, role_id: &RoleId) -> DispatchResult;
 	fn check_claim_account(claimer: &RoleId, role: Role) -> DispatchResult;
 	fn check_account(who: &RoleId, role: Role) -> DispatchResult;
 	fn check_union(who: &RoleId, role1: Role, role2: Role) -> DispatchResult;
+	/// Check in the permission table that `who`, or the parent organisation of a sub-account,
+	/// may perform `capability` and return the organisation the action is attributed to.
+	fn has_permission(who: &RoleId, capability: Capability) -> Result<RoleId, DispatchError>;
+	/// Parent organisation of a sub-account.
+	fn parent_of(who: &RoleId) -> Option<RoleId>;
+	/// Roles held by `who`, whatever their status.
+	fn roles_of(who: &RoleId) -> Vec<Role>;
+	/// Status of `role` held by `who`.
+	fn role_status(who: &RoleId, role: Role) -> Option<RoleStatus>;
+	/// Organisation the actions of `who` are attributed to, `who` itself unless a sub-account.
+	fn organisation_of(who: &RoleId) -> RoleId;
+	/// Jurisdiction in the approved profile of the organisation of `who`.
+	fn jurisdiction_of(who: &RoleId) -> Option<Vec<u8>>;
+	/// Key bound to `who`.
+	fn account_of(who: &RoleId) -> Option<AccountId>;
+	/// Check that `who` holds an unexpired delegation of `capability` from `principal`.
+	fn check_delegate(
+		who: &AccountId,
+		principal: &RoleId,
+		capability: Capability,
+	) -> DispatchResult;
+}
+
+/// Erases the records another pallet keeps about a role ID whose account is erased.
+pub trait OnErasure {
+	fn on_erasure(who: &RoleId);
+}
+
+impl OnErasure for () {
+	fn on_erasure(_who: &RoleId) {}
+}
+
+/// Refuses the removal of a sub-account another pallet still keeps live records under.
+pub trait OnSubAccountRemoval {
+	fn can_remove(sub: &RoleId) -> DispatchResult;
+}
+
+impl OnSubAccountRemoval for () {
+	fn can_remove(_sub: &RoleId) -> DispatchResult {
+		Ok(())
+	}
 }
 
 #[frame_support::pallet]
@@ -42,12 +98,56 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type MaxListSize: Get<u32>;
 		type UnixTime: UnixTime;
+		/// Granularity in milliseconds of the role expiry sweep.
+		#[pallet::constant]
+		type ExpiryPeriod: Get<u64>;
+		/// Time in milliseconds a proposal stays open for votes.
+		#[pallet::constant]
+		type ProposalLifetime: Get<u64>;
+		/// Maximum length in bytes of a profile field.
+		#[pallet::constant]
+		type MaxProfileLength: Get<u32>;
+		/// Maximum number of document hashes attached to a profile.
+		#[pallet::constant]
+		type MaxDocuments: Get<u32>;
+		/// Time in milliseconds before a key recovery can be completed, the window to cancel it.
+		#[pallet::constant]
+		type RecoveryDelay: Get<u64>;
+		/// Longest time in milliseconds a delegation to an operator key may run.
+		#[pallet::constant]
+		type MaxDelegationPeriod: Get<u64>;
+		/// Records of other pallets erased together with an account.
+		type OnErasure: OnErasure;
+		/// Records of other pallets a sub-account is not removed with.
+		type OnSubAccountRemoval: OnSubAccountRemoval;
+		/// Currency in which organisations put up the bond of their roles.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Bond reserved for each manufacture, organisation or distributer role.
+		#[pallet::constant]
+		type RoleBond: Get<BalanceOf<Self>>;
+		/// Number of fee-free transactions a key of an approved role may send per period.
+		#[pallet::constant]
+		type FreeTransactions: Get<u32>;
+		/// Length in blocks of the period the fee-free transactions are counted in.
+		#[pallet::constant]
+		type FreeTransactionPeriod: Get<Self::BlockNumber>;
+		/// Maximum number of keys, and of services, listed in a DID document.
+		#[pallet::constant]
+		type MaxDidEntries: Get<u32>;
+		/// Maximum length in bytes of a DID document field.
+		#[pallet::constant]
+		type MaxDidLength: Get<u32>;
 	}
 
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 	pub type VaccineTypeIndex = u32;
 	pub type VaccineIndex = u32;
 	pub type RecognitionId = u32;
 	pub type String = Vec<u8>;
+	/// Code explaining why a system manager changed the status of a role.
+	pub type ReasonCode = u32;
 
 	#[derive(
 		Encode, Decode, Ord, PartialOrd, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo,
@@ -61,6 +161,62 @@ pub mod pallet {
 		USER,
 	}
 
+	impl Role {
+		/// Capabilities of the role until system managers set its permissions.
+		pub fn default_capabilities(&self) -> &'static [Capability] {
+			match self {
+				Role::SYSMAN => &[Capability::RegisterVaccineType, Capability::RecallVaccine],
+				Role::VM => &[
+					Capability::RegisterVaccine,
+					Capability::TransferVaccine,
+					Capability::ReceiveVaccine,
+					Capability::RecallVaccine,
+				],
+				Role::VAO => &[Capability::ApproveVaccine],
+				Role::VAD => &[
+					Capability::TransferVaccine,
+					Capability::ReceiveVaccine,
+					Capability::AdministerVaccine,
+				],
+				Role::USER => &[],
+			}
+		}
+	}
+
+	/// Vaccine action guarded by the permission table.
+	#[derive(
+		Encode, Decode, Ord, PartialOrd, Clone, Copy, Eq, PartialEq, RuntimeDebug, TypeInfo,
+	)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum Capability {
+		RegisterVaccine,
+		TransferVaccine,
+		ReceiveVaccine,
+		ApproveVaccine,
+		// hand a vaccine over to a user for inoculation
+		AdministerVaccine,
+		RegisterVaccineType,
+		// pull vials out of circulation, manufactures only their own
+		RecallVaccine,
+	}
+
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum SubAccountKind {
+		Warehouse,
+		Clinic,
+		Staff,
+	}
+
+	/// Site or member of an organisation acting with a subset of its capabilities.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct SubAccount {
+		pub parent: RoleId,
+		pub kind: SubAccountKind,
+		pub capabilities: Vec<Capability>,
+	}
+
 	#[derive(
 		Encode, Decode, Ord, PartialOrd, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo,
 	)]
@@ -69,6 +225,10 @@ pub mod pallet {
 		Approved,
 		Revoked,
 		Pending,
+		// suspended until the given unix time in milliseconds
+		Suspended { until: u64 },
+		// the approval window has passed, the role has to be claimed again
+		Expired,
 	}
 
 	impl Default for RoleStatus {
@@ -77,15 +237,196 @@ pub mod pallet {
 		}
 	}
 
-	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub struct Account {
-		role: Role,
-		status: RoleStatus,
+		// every role held by the account with its own status
+		pub roles: BTreeMap<Role, RoleStatus>,
+	}
+
+	impl Account {
+		pub fn new(role: Role, status: RoleStatus) -> Self {
+			let mut roles = BTreeMap::new();
+			roles.insert(role, status);
+			Account { roles }
+		}
+	}
+
+	/// Role requested by an organisation, waiting for a system manager review.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct Claim {
+		pub role: Role,
+		// unix time in milliseconds
+		pub claimed_at: u64,
+	}
+
+	/// Organisation details and hashes of the licence documents a system manager reviews.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct OrgProfile<BoundedString, BoundedHashList> {
+		pub legal_name: BoundedString,
+		pub licence_number: BoundedString,
+		pub jurisdiction: BoundedString,
+		// e.g. blake2 hash of the licence certificate
+		pub documents: BoundedHashList,
+		// unix time in milliseconds
+		pub submitted_at: u64,
+		// set once a system manager approved the profile
+		pub reviewed_by: Option<RoleId>,
+		pub reviewed_at: Option<u64>,
+	}
+
+	pub type ProfileOf<T> = OrgProfile<
+		BoundedVec<u8, <T as Config>::MaxProfileLength>,
+		BoundedVec<<T as frame_system::Config>::Hash, <T as Config>::MaxDocuments>,
+	>;
+
+	/// Rebinding of a role ID whose key was lost, started by a system manager.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Recovery<AccountId> {
+		pub new_account: AccountId,
+		pub initiated_by: RoleId,
+		// unix time in milliseconds from which the recovery can be completed
+		pub execute_after: u64,
+		// the current key objected, the recovery waits for a system manager to confirm it
+		pub disputed: bool,
+	}
+
+	/// Capabilities a role holder lends to an operator key, such as the tablet of a nurse.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Delegation {
+		pub capabilities: Vec<Capability>,
+		// unix time in milliseconds at which the delegation lapses
+		pub expires_at: u64,
+	}
+
+	/// Funds reserved for an organisation role from the key that claimed or registered it.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Bond<AccountId, Balance> {
+		// stays with this key when the role ID is bound to another one
+		pub who: AccountId,
+		pub amount: Balance,
+	}
+
+	/// What remains of an erased account, stored under the hash of its role ID so that the
+	/// supply chain history referring to the role ID stays consistent.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Tombstone {
+		pub erased_at: u64,
+		pub confirmed_by: RoleId,
+	}
+
+	/// Signature scheme of a key listed in a DID document.
+	#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub enum KeyType {
+		Sr25519,
+		Ed25519,
+		Ecdsa,
+	}
+
+	impl KeyType {
+		/// Length in bytes of a public key of the scheme.
+		pub fn public_key_len(&self) -> usize {
+			match self {
+				KeyType::Sr25519 | KeyType::Ed25519 => 32,
+				// compressed
+				KeyType::Ecdsa => 33,
+			}
+		}
+	}
+
+	/// Key partners verify off-chain signatures of the role ID with.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct VerificationKey {
+		// fragment of the key in the DID URL, e.g. `key-1`
+		pub id: String,
+		pub key_type: KeyType,
+		pub public_key: Vec<u8>,
+	}
+
+	/// Off-chain service of the role ID, e.g. the endpoint issuing vaccination certificates.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct DidService {
+		// fragment of the service in the DID URL
+		pub id: String,
+		pub service_type: String,
+		pub endpoint: String,
+	}
+
+	/// Document of `did:vaccine:<RoleId>` maintained by the owner of the role ID.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct DidDocument {
+		// `None` when the role ID controls its document itself
+		pub controller: Option<RoleId>,
+		pub keys: Vec<VerificationKey>,
+		pub services: Vec<DidService>,
+		// unix time in milliseconds
+		pub updated_at: u64,
+	}
+
+	/// What `did:vaccine:<RoleId>` resolves to.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct DidResolution<AccountId> {
+		// key bound to the role ID, listed under `ACCOUNT_KEY_ID`
+		pub account: AccountId,
+		pub document: Option<DidDocument>,
+	}
+
+	/// Fragment of the key bound to a role ID in its DID document.
+	pub const ACCOUNT_KEY_ID: &[u8] = b"account";
+
+	/// System manager action which only takes effect once enough system managers voted for it.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub enum ProposalAction<AccountId> {
+		ApproveRole { target: RoleId, role: Role, valid_for: Option<u64> },
+		AddSystem(RoleId),
+		RemoveSystem(RoleId),
+		SetPermissions { role: Role, capabilities: Vec<Capability> },
+		SlashBond { target: RoleId, role: Role },
+		RevokeSystem { target: RoleId, reason: ReasonCode },
+		SuspendSystem { target: RoleId, duration: u64, reason: ReasonCode },
+		ReinstateRole { target: RoleId, role: Role, reason: ReasonCode },
+		RecoverSystem { target: RoleId, new_account: AccountId },
+	}
+
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Proposal<AccountId> {
+		pub action: ProposalAction<AccountId>,
+		// role IDs of the system managers who voted for the action
+		pub votes: Vec<RoleId>,
+		// unix time in milliseconds after which the proposal lapses
+		pub deadline: u64,
 	}
 
+	/// Change of the system manager set or its governance.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub enum AdminChange {
+		AddSystem(RoleId),
+		RemoveSystem(RoleId),
+		ThresholdSet(u32),
+		PermissionsSet(Role, Vec<Capability>),
+		BondSlashed(RoleId, Role),
+		SystemRevoked(RoleId, ReasonCode),
+		SystemSuspended(RoleId, u64, ReasonCode),
+		SystemReinstated(RoleId, ReasonCode),
+	}
+
+	/// Audit log entry recording who changed what and when.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct AdminLogEntry<BlockNumber> {
+		// system managers who carried the change, empty for root
+		pub who: Vec<RoleId>,
+		pub change: AdminChange,
+		// unix time in milliseconds
+		pub at: u64,
+		pub block: BlockNumber,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -94,12 +435,147 @@ pub mod pallet {
 	#[pallet::getter(fn accounts)]
 	pub type Accounts<T> = StorageMap<_, Blake2_128Concat, RoleId, Account, OptionQuery>;
 
-	// Account ID => Role
+	// Role ID => signing Account ID
 	#[pallet::storage]
 	#[pallet::getter(fn account_role)]
 	pub type AccountRole<T: Config> =
 		StorageMap<_, Blake2_128Concat, RoleId, T::AccountId, OptionQuery>;
 
+	// signing Account ID => Role ID
+	#[pallet::storage]
+	#[pallet::getter(fn role_id_of)]
+	pub type RoleIdOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, RoleId, OptionQuery>;
+
+	// sub-account Role ID => SubAccount struct
+	#[pallet::storage]
+	#[pallet::getter(fn sub_accounts)]
+	pub type SubAccounts<T> = StorageMap<_, Blake2_128Concat, RoleId, SubAccount, OptionQuery>;
+
+	// (parent Role ID, sub-account Role ID) => (), the sub-accounts of an organisation
+	#[pallet::storage]
+	pub type SubAccountsOf<T> =
+		StorageDoubleMap<_, Blake2_128Concat, RoleId, Blake2_128Concat, RoleId, (), OptionQuery>;
+
+	// Role => capabilities of its approved holders, the defaults apply to roles not set
+	#[pallet::storage]
+	#[pallet::getter(fn permissions)]
+	pub type Permissions<T> = StorageMap<_, Blake2_128Concat, Role, Vec<Capability>, OptionQuery>;
+
+	// Role ID => reviewed OrgProfile struct
+	#[pallet::storage]
+	#[pallet::getter(fn profiles)]
+	pub type Profiles<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoleId, ProfileOf<T>, OptionQuery>;
+
+	// Role ID => OrgProfile struct submitted by the owner, waiting for a system manager review
+	#[pallet::storage]
+	#[pallet::getter(fn pending_profiles)]
+	pub type PendingProfiles<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoleId, ProfileOf<T>, OptionQuery>;
+
+	// Role ID => Recovery struct, pending key recoveries
+	#[pallet::storage]
+	#[pallet::getter(fn recoveries)]
+	pub type Recoveries<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoleId, Recovery<T::AccountId>, OptionQuery>;
+
+	// (Role ID, operator key) => Delegation struct
+	#[pallet::storage]
+	#[pallet::getter(fn delegations)]
+	pub type Delegations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		RoleId,
+		Blake2_128Concat,
+		T::AccountId,
+		Delegation,
+		OptionQuery,
+	>;
+
+	// (Role ID, Role) => Bond struct of an organisation role
+	#[pallet::storage]
+	#[pallet::getter(fn bonds)]
+	pub type Bonds<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		RoleId,
+		Blake2_128Concat,
+		Role,
+		Bond<T::AccountId, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	// account ID => (period, fee-free transactions sent in the period)
+	#[pallet::storage]
+	#[pallet::getter(fn free_transactions_used)]
+	pub type FreeTransactionsUsed<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, u32), OptionQuery>;
+
+	// Role ID => unix time in milliseconds of the erasure request of the user
+	#[pallet::storage]
+	#[pallet::getter(fn erasure_requests)]
+	pub type ErasureRequests<T> = StorageMap<_, Blake2_128Concat, RoleId, u64, OptionQuery>;
+
+	// hash of an erased role ID => Tombstone struct
+	#[pallet::storage]
+	#[pallet::getter(fn tombstones)]
+	pub type Tombstones<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, Tombstone, OptionQuery>;
+
+	// Role ID => DID document
+	#[pallet::storage]
+	#[pallet::getter(fn did_documents)]
+	pub type DidDocuments<T> = StorageMap<_, Blake2_128Concat, RoleId, DidDocument, OptionQuery>;
+
+	// (Role ID, Role) => Claim struct, the review queue of self-service claims
+	#[pallet::storage]
+	#[pallet::getter(fn pending_claims)]
+	pub type PendingClaims<T> =
+		StorageDoubleMap<_, Blake2_128Concat, RoleId, Blake2_128Concat, Role, Claim, OptionQuery>;
+
+	// (Role ID, Role) => end of the approval window in unix time milliseconds
+	#[pallet::storage]
+	#[pallet::getter(fn role_expiry)]
+	pub type RoleExpiry<T> =
+		StorageDoubleMap<_, Blake2_128Concat, RoleId, Blake2_128Concat, Role, u64, OptionQuery>;
+
+	// expiry period index => roles whose approval window ends in that period
+	#[pallet::storage]
+	pub type ExpiryQueue<T> =
+		StorageMap<_, Twox64Concat, u64, Vec<(RoleId, Role, u64)>, ValueQuery>;
+
+	// next expiry period to be swept
+	#[pallet::storage]
+	pub type NextSweepPeriod<T> = StorageValue<_, u64, OptionQuery>;
+
+	// hash of the proposed action => open proposal
+	#[pallet::storage]
+	#[pallet::getter(fn proposals)]
+	pub type Proposals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, Proposal<T::AccountId>, OptionQuery>;
+
+	#[pallet::type_value]
+	pub fn DefaultThreshold() -> u32 {
+		1
+	}
+
+	// number of system manager votes needed to carry out a proposal
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_threshold)]
+	pub type ProposalThreshold<T> = StorageValue<_, u32, ValueQuery, DefaultThreshold>;
+
+	// index => admin audit log entry, in order of the changes
+	#[pallet::storage]
+	#[pallet::getter(fn admin_log)]
+	pub type AdminLog<T: Config> =
+		StorageMap<_, Twox64Concat, u64, AdminLogEntry<T::BlockNumber>, OptionQuery>;
+
+	// number of entries in the admin audit log
+	#[pallet::storage]
+	#[pallet::getter(fn admin_log_count)]
+	pub type AdminLogCount<T> = StorageValue<_, u64, ValueQuery>;
+
 	/// Store admin user account for special purpose
 	#[pallet::storage]
 	#[pallet::getter(fn system_manager)]
@@ -107,23 +583,24 @@ pub mod pallet {
 
 	// Alice is sysman by default
 	#[pallet::genesis_config]
-	pub struct GenesisConfig {
-		pub genesis_account: Vec<Vec<u8>>,
+	pub struct GenesisConfig<T: Config> {
+		pub genesis_account: Vec<(RoleId, T::AccountId)>,
 	}
 
 	#[cfg(feature = "std")]
-	impl Default for GenesisConfig {
+	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			Self { genesis_account: Default::default() }
 		}
 	}
 
 	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
-			for role_id in &self.genesis_account {
-				let account = Account { role: Role::SYSMAN, status: RoleStatus::Approved };
+			for (role_id, account_id) in &self.genesis_account {
+				let account = Account::new(Role::SYSMAN, RoleStatus::Approved);
 				<Accounts<T>>::insert(role_id, account);
+				Pallet::<T>::bind_account(role_id, account_id);
 				SystemManager::<T>::insert(role_id, true);
 			}
 		}
@@ -131,12 +608,75 @@ pub mod pallet {
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T> {
-		Claimed(RoleId),
-		Approved(RoleId),
+	pub enum Event<T: Config> {
+		Claimed(RoleId, Role),
+		Approved(RoleId, Role),
+		Rejected(RoleId, Role),
 		AccountRegisted(RoleId),
 		RemoveSystem(RoleId),
 		AddSystem(RoleId),
+		/// [role ID, role, reason]
+		Revoked(RoleId, Role, ReasonCode),
+		/// [role ID, role, suspended until, reason]
+		Suspended(RoleId, Role, u64, ReasonCode),
+		/// [role ID, role, reason]
+		Reinstated(RoleId, Role, ReasonCode),
+		/// [role ID, role]
+		RoleExpired(RoleId, Role),
+		/// [proposal hash, proposer]
+		Proposed(T::Hash, RoleId),
+		/// [proposal hash, voter, current votes]
+		Voted(T::Hash, RoleId, u32),
+		/// [proposal hash]
+		ProposalExpired(T::Hash),
+		/// [threshold]
+		ThresholdSet(u32),
+		/// [parent, sub-account, kind]
+		SubAccountCreated(RoleId, RoleId, SubAccountKind),
+		/// [parent, sub-account]
+		SubAccountRemoved(RoleId, RoleId),
+		/// [role ID]
+		ProfileSubmitted(RoleId),
+		/// [role ID, system manager]
+		ProfileApproved(RoleId, RoleId),
+		/// [role ID, system manager]
+		ProfileRejected(RoleId, RoleId),
+		/// [role ID, old key, new key]
+		KeyRotated(RoleId, T::AccountId, T::AccountId),
+		/// [role ID, new key, completable after]
+		RecoveryInitiated(RoleId, T::AccountId, u64),
+		/// [role ID, cancelled by]
+		RecoveryCancelled(RoleId, RoleId),
+		/// [role ID, new key]
+		KeyRecovered(RoleId, T::AccountId),
+		/// [role, capabilities]
+		PermissionsSet(Role, Vec<Capability>),
+		/// [principal, operator key, capabilities, expires at]
+		Delegated(RoleId, T::AccountId, Vec<Capability>, u64),
+		/// [principal, operator key]
+		DelegationRevoked(RoleId, T::AccountId),
+		/// [role ID]
+		ErasureRequested(RoleId),
+		/// [tombstone, system manager]
+		AccountErased(T::Hash, RoleId),
+		/// [role ID, role, amount]
+		BondReserved(RoleId, Role, BalanceOf<T>),
+		/// [role ID, role, amount]
+		BondReleased(RoleId, Role, BalanceOf<T>),
+		/// [role ID, role, amount]
+		BondSlashed(RoleId, Role, BalanceOf<T>),
+		/// [role ID, role]
+		RoleResigned(RoleId, Role),
+		/// [role ID]
+		DidDocumentSet(RoleId),
+		/// [role ID]
+		DidDocumentRemoved(RoleId),
+		/// the current key objected to the recovery [role ID]
+		RecoveryDisputed(RoleId),
+		/// [role ID, system manager]
+		RecoveryConfirmed(RoleId, RoleId),
+		/// [role ID, key]
+		KeyBound(RoleId, T::AccountId),
 	}
 
 	// Errors inform users that something went wrong.
@@ -152,34 +692,236 @@ pub mod pallet {
 		InvalidStatus,
 		NotFoundRole,
 		PermissionDeny,
+		/// The signer is not the account bound to the claimed role ID.
+		NotRoleOwner,
+		/// The signing account is already bound to another role ID.
+		AccountAlreadyBound,
+		/// The role is suspended for the time being.
+		AccountSuspended,
+		/// The approval window of the role has passed.
+		RoleExpired,
+		/// The system manager already voted for the proposal.
+		AlreadyVoted,
+		/// There is no open proposal with the given hash.
+		NotFoundProposal,
+		/// The proposal is still open for votes.
+		ProposalNotExpired,
+		/// The threshold must be at least one vote.
+		InvalidThreshold,
+		/// The role ID is not a system manager.
+		NotSystemManager,
+		/// The set of system managers can not become empty.
+		LastSystemManager,
+		/// The role ID is not a sub-account of the organisation.
+		NotSubAccount,
+		/// A profile field exceeds `MaxProfileLength`.
+		ProfileTooLong,
+		/// More documents than `MaxDocuments` are attached.
+		TooManyDocuments,
+		/// There is no profile update waiting for a review.
+		NotFoundProfile,
+		/// A key recovery is already in progress for the role ID.
+		RecoveryInProgress,
+		/// There is no key recovery in progress for the role ID.
+		NotFoundRecovery,
+		/// The recovery delay has not passed yet.
+		RecoveryNotReady,
+		/// A delegation needs a capability and a duration of at most `MaxDelegationPeriod`.
+		InvalidDelegation,
+		/// There is no delegation from the role ID to the key.
+		NotFoundDelegation,
+		/// The delegation has lapsed.
+		DelegationExpired,
+		/// Only plain user accounts can be erased.
+		NotErasable,
+		/// The user did not request an erasure.
+		NotFoundErasure,
+		/// The role ID belonged to an erased account and can not be used again.
+		AlreadyErased,
+		/// No bond is reserved for the role.
+		NotFoundBond,
+		/// More keys or services than `MaxDidEntries` are listed.
+		TooManyDidEntries,
+		/// A DID document field exceeds `MaxDidLength`.
+		DidFieldTooLong,
+		/// A key does not match its scheme, or a fragment is empty or used twice.
+		InvalidDidEntry,
+		/// The role ID has no DID document.
+		NotFoundDidDocument,
+		/// The current key objected to the recovery, a system manager has to confirm it.
+		RecoveryDisputed,
+		/// The current key did not object to the recovery.
+		RecoveryNotDisputed,
+		/// A system manager role can not be approved for a limited time.
+		ExpiringSystemManager,
+		/// The system manager who started the recovery does not confirm it as well.
+		RecoveryInitiator,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			Self::sweep_expired_roles()
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v1::migrate::<T>()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// vote for approving a claimed role, valid for `valid_for` milliseconds or without end,
+		/// the role is approved once the proposal threshold is reached
 		#[pallet::weight(10_000)]
 		pub fn approve_role(
 			origin: OriginFor<T>,
 			system: RoleId,
 			target: RoleId,
+			role: Role,
+			valid_for: Option<u64>,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
-			ensure!(Self::only_system(system.clone()), Error::<T>::PermissionDeny);
+			let who = ensure_signed(origin)?;
 			// only sysman execute
-			Self::check_account(&system, Role::SYSMAN)?;
+			Self::ensure_sysman(&who, &system)?;
+
+			Self::vote(system, ProposalAction::ApproveRole { target, role, valid_for })
+		}
 
-			let mut account = <Accounts<T>>::get(&target).unwrap();
+		/// reject a pending claim, the role ID is released again
+		#[pallet::weight(10_000)]
+		pub fn reject_role(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+			role: Role,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
 
-			ensure!(account.status != RoleStatus::Approved, Error::<T>::InvalidStatus);
+			ensure!(<PendingClaims<T>>::contains_key(&target, &role), Error::<T>::NotClaimed);
+			if Self::is_last_role(&target, &role) {
+				Self::ensure_releasable(&target)?;
+			}
 
-			account.status = RoleStatus::Approved;
+			<PendingClaims<T>>::remove(&target, &role);
+			Self::release_bond(&target, &role);
+			let released = <Accounts<T>>::mutate_exists(&target, |maybe_account| {
+				if let Some(account) = maybe_account {
+					account.roles.remove(&role);
+					if account.roles.is_empty() {
+						*maybe_account = None;
+					}
+				}
+				maybe_account.is_none()
+			});
+			if released {
+				Self::release_role_id(&target);
+			}
 
-			// Update storage.
-			<Accounts<T>>::insert(&target, &account);
-			SystemManager::<T>::insert(target.clone(), true);
+			Self::deposit_event(Event::Rejected(target, role));
+			Ok(())
+		}
 
-			// Emit an event.
-			Self::deposit_event(Event::Approved(target));
-			// Return a successful DispatchResultWithPostInfo
+		/// revoke an approved or suspended role for good, e.g. to cut off a bad actor, a system
+		/// manager role only once the proposal threshold is reached
+		#[pallet::weight(10_000)]
+		pub fn revoke_role(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+			role: Role,
+			reason: ReasonCode,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+			if role == Role::SYSMAN {
+				return Self::vote(system, ProposalAction::RevokeSystem { target, reason })
+			}
+
+			Self::update_status(&target, &role, Self::revoked)?;
+
+			Self::deposit_event(Event::Revoked(target, role, reason));
+			Ok(())
+		}
+
+		/// suspend an approved role for `duration` milliseconds, a system manager role only once
+		/// the proposal threshold is reached
+		#[pallet::weight(10_000)]
+		pub fn suspend_role(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+			role: Role,
+			duration: u64,
+			reason: ReasonCode,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+			if role == Role::SYSMAN {
+				let action = ProposalAction::SuspendSystem { target, duration, reason };
+				return Self::vote(system, action)
+			}
+
+			let until = Self::now().saturating_add(duration);
+			Self::update_status(&target, &role, |status| Self::suspended(status, until))?;
+
+			Self::deposit_event(Event::Suspended(target, role, until, reason));
+			Ok(())
+		}
+
+		/// vote for lifting a suspension or revocation, the role is reinstated once the proposal
+		/// threshold is reached
+		#[pallet::weight(10_000)]
+		pub fn reinstate_role(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+			role: Role,
+			reason: ReasonCode,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+
+			Self::vote(system, ProposalAction::ReinstateRole { target, role, reason })
+		}
+
+		/// request an organisation role (VM, VAO or VAD) for the signing key, an organisation
+		/// already holding a role can claim another one for the same role ID or renew an expired
+		/// one
+		#[pallet::weight(10_000)]
+		pub fn claim_role(origin: OriginFor<T>, role_id: RoleId, role: Role) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			// only organisation roles are claimable
+			ensure!(matches!(role, Role::VM | Role::VAO | Role::VAD), Error::<T>::InvalidRole);
+			Self::check_claim_account(&role_id, role.clone())?;
+			if <Accounts<T>>::contains_key(&role_id) {
+				Self::check_owner(&who, &role_id)?;
+			} else {
+				ensure!(!Self::is_erased(&role_id), Error::<T>::AlreadyErased);
+				ensure!(!<AccountRole<T>>::contains_key(&role_id), Error::<T>::AlreadyRegistered);
+				ensure!(!<RoleIdOf<T>>::contains_key(&who), Error::<T>::AccountAlreadyBound);
+			}
+			Self::reserve_bond(&role_id, &role, &who)?;
+			if !<AccountRole<T>>::contains_key(&role_id) {
+				Self::bind_account(&role_id, &who);
+			}
+
+			let claimed_at = Self::now();
+			<Accounts<T>>::mutate(&role_id, |account| {
+				account
+					.get_or_insert_with(Default::default)
+					.roles
+					.insert(role.clone(), RoleStatus::Pending);
+			});
+			<PendingClaims<T>>::insert(&role_id, &role, Claim { role: role.clone(), claimed_at });
+
+			Self::deposit_event(Event::Claimed(role_id, role));
 			Ok(())
 		}
 
@@ -187,109 +929,1343 @@ pub mod pallet {
 		pub fn register_account(
 			origin: OriginFor<T>,
 			role_id: RoleId,
+			account_id: T::AccountId,
 			role: Role,
 		) -> DispatchResult {
 			ensure_root(origin)?;
 			match <Accounts<T>>::try_get(&role_id) {
 				Err(_) => {
-					<Accounts<T>>::insert(&role_id, Account { role, status: Default::default() });
+					ensure!(!Self::is_erased(&role_id), Error::<T>::AlreadyErased);
+					// a sub-account is bound without holding a role itself
+					ensure!(
+						!<AccountRole<T>>::contains_key(&role_id),
+						Error::<T>::AlreadyRegistered
+					);
+					ensure!(
+						!<RoleIdOf<T>>::contains_key(&account_id),
+						Error::<T>::AccountAlreadyBound
+					);
+					Self::reserve_bond(&role_id, &role, &account_id)?;
+					<Accounts<T>>::insert(&role_id, Account::new(role, Default::default()));
+					Self::bind_account(&role_id, &account_id);
+				},
+				Ok(mut account) => {
+					// add another role to the same organisation
+					Self::check_owner(&account_id, &role_id)?;
+					ensure!(!account.roles.contains_key(&role), Error::<T>::AlreadyRegistered);
+					Self::reserve_bond(&role_id, &role, &account_id)?;
+					account.roles.insert(role, Default::default());
+					<Accounts<T>>::insert(&role_id, account);
 				},
-				Ok(_) => Err(Error::<T>::AlreadyRegistered)?,
 			}
 			// Return a successful DispatchResultWithPostInfo
 			Self::deposit_event(Event::AccountRegisted(role_id));
 			Ok(())
 		}
 
-		/// add admin for special purposes
+		/// give up a role and get its bond back, not while the role is suspended or revoked
 		#[pallet::weight(10_000)]
-		pub fn add_system(origin: OriginFor<T>, system: RoleId, user: RoleId) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+		pub fn resign_role(origin: OriginFor<T>, role_id: RoleId, role: Role) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_owner(&who, &role_id)?;
+			// system managers leave through `remove_system`
+			ensure!(role != Role::SYSMAN, Error::<T>::InvalidRole);
+
+			let account = <Accounts<T>>::get(&role_id).ok_or(Error::<T>::NotFoundRole)?;
+			match account.roles.get(&role) {
+				Some(RoleStatus::Suspended { .. }) => Err(Error::<T>::AccountSuspended)?,
+				// the bond stays at stake until the revocation is lifted or the bond slashed
+				Some(RoleStatus::Revoked) => Err(Error::<T>::AlreadyRevoked)?,
+				Some(_) => {},
+				None => Err(Error::<T>::InvalidRole)?,
+			}
+			if account.roles.len() == 1 {
+				Self::ensure_releasable(&role_id)?;
+			}
 
-			if !Self::only_system(system) {
-				return Err(Error::<T>::PermissionDeny)?
+			<PendingClaims<T>>::remove(&role_id, &role);
+			<RoleExpiry<T>>::remove(&role_id, &role);
+			Self::release_bond(&role_id, &role);
+			let released = <Accounts<T>>::mutate_exists(&role_id, |maybe_account| {
+				if let Some(account) = maybe_account {
+					account.roles.remove(&role);
+					if account.roles.is_empty() {
+						*maybe_account = None;
+					}
+				}
+				maybe_account.is_none()
+			});
+			if released {
+				Self::release_role_id(&role_id);
 			}
 
-			SystemManager::<T>::insert(&user, true);
-			Self::deposit_event(Event::AddSystem(user));
+			Self::deposit_event(Event::RoleResigned(role_id, role));
 			Ok(())
 		}
 
-		/// remove admin for special purposes
+		/// create a warehouse, clinic or staff sub-account of an approved VM or VAD, acting
+		/// with a subset of the capabilities of the organisation
 		#[pallet::weight(10_000)]
-		pub fn remove_system(origin: OriginFor<T>, system: RoleId, user: RoleId) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
-			if !Self::only_system(system) {
-				return Err(Error::<T>::PermissionDeny)?
+		pub fn create_sub_account(
+			origin: OriginFor<T>,
+			parent: RoleId,
+			sub: RoleId,
+			account_id: T::AccountId,
+			kind: SubAccountKind,
+			mut capabilities: Vec<Capability>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_owner(&who, &parent)?;
+
+			// only top level manufactures and distributers own sub-accounts
+			ensure!(!<SubAccounts<T>>::contains_key(&parent), Error::<T>::PermissionDeny);
+			Self::check_union(&parent, Role::VM, Role::VAD)?;
+			for capability in &capabilities {
+				Self::check_role_capability(&parent, *capability)?;
 			}
-			SystemManager::<T>::remove(&user);
-			Self::deposit_event(Event::RemoveSystem(user));
+			capabilities.sort();
+			capabilities.dedup();
 
+			ensure!(!Self::is_erased(&sub), Error::<T>::AlreadyErased);
+			ensure!(!<AccountRole<T>>::contains_key(&sub), Error::<T>::AlreadyRegistered);
+			ensure!(!<RoleIdOf<T>>::contains_key(&account_id), Error::<T>::AccountAlreadyBound);
+
+			Self::bind_account(&sub, &account_id);
+			<SubAccounts<T>>::insert(
+				&sub,
+				SubAccount { parent: parent.clone(), kind: kind.clone(), capabilities },
+			);
+			<SubAccountsOf<T>>::insert(&parent, &sub, ());
+
+			Self::deposit_event(Event::SubAccountCreated(parent, sub, kind));
 			Ok(())
 		}
-	}
 
-	/* ----------------------------------------------helper function
-	 * ------------------------------------------------- */
-	impl<T: Config> AccountPallet for Pallet<T> {
-		fn check_claim_account(claimer: &RoleId, role: Role) -> DispatchResult {
-			let account = <Accounts<T>>::get(claimer).unwrap();
-			match account.role {
-				a if a == role => Err(Error::<T>::AlreadyClaimed)?,
-				Role::USER => match account.status {
-					RoleStatus::Approved => Err(Error::<T>::AlreadyApproved)?,
-					RoleStatus::Revoked => Err(Error::<T>::AlreadyRevoked)?,
-					RoleStatus::Pending => Err(Error::<T>::AlreadyClaimed)?,
-				},
-				_ => Err(Error::<T>::InvalidRole)?,
-			}
+		/// remove a sub-account, its key is released, refused while it still holds vials
+		#[pallet::weight(10_000)]
+		pub fn remove_sub_account(
+			origin: OriginFor<T>,
+			parent: RoleId,
+			sub: RoleId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_owner(&who, &parent)?;
+			ensure!(<SubAccountsOf<T>>::contains_key(&parent, &sub), Error::<T>::NotSubAccount);
+			T::OnSubAccountRemoval::can_remove(&sub)?;
+
+			<SubAccounts<T>>::remove(&sub);
+			<SubAccountsOf<T>>::remove(&parent, &sub);
+			Self::unbind_account(&sub);
+
+			Self::deposit_event(Event::SubAccountRemoved(parent, sub));
+			Ok(())
 		}
 
-		fn check_account(who: &RoleId, role: Role) -> DispatchResult {
-			let account = <Accounts<T>>::get(who).unwrap();
-			log::info!("Here ");
-			match account.role {
-
-				a if a == role => match account.status {
-					
-					RoleStatus::Approved => return Ok(()),
-					RoleStatus::Revoked => Err(Error::<T>::AlreadyRevoked)?,
-					RoleStatus::Pending => Err(Error::<T>::NotApproved)?,
-				},
-				_ => {
-					log::info!("Here 3");
-					return Err(Error::<T>::InvalidRole)?;},
+		/// submit organisation details and licence document hashes, they replace the current
+		/// profile once a system manager approved them
+		#[pallet::weight(10_000)]
+		pub fn update_profile(
+			origin: OriginFor<T>,
+			role_id: RoleId,
+			legal_name: Vec<u8>,
+			licence_number: Vec<u8>,
+			jurisdiction: Vec<u8>,
+			documents: Vec<T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_owner(&who, &role_id)?;
+			ensure!(<Accounts<T>>::contains_key(&role_id), Error::<T>::NotFoundRole);
+
+			let bounded = |field: Vec<u8>| {
+				BoundedVec::<u8, T::MaxProfileLength>::try_from(field)
+					.map_err(|_| Error::<T>::ProfileTooLong)
+			};
+			let profile: ProfileOf<T> = OrgProfile {
+				legal_name: bounded(legal_name)?,
+				licence_number: bounded(licence_number)?,
+				jurisdiction: bounded(jurisdiction)?,
+				documents: BoundedVec::try_from(documents)
+					.map_err(|_| Error::<T>::TooManyDocuments)?,
+				submitted_at: Self::now(),
+				reviewed_by: None,
+				reviewed_at: None,
+			};
+			<PendingProfiles<T>>::insert(&role_id, profile);
+
+			Self::deposit_event(Event::ProfileSubmitted(role_id));
+			Ok(())
+		}
+
+		/// approve or reject the profile update of an organisation
+		#[pallet::weight(10_000)]
+		pub fn review_profile(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+			approve: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+
+			let mut profile =
+				<PendingProfiles<T>>::take(&target).ok_or(Error::<T>::NotFoundProfile)?;
+			if approve {
+				profile.reviewed_by = Some(system.clone());
+				profile.reviewed_at = Some(Self::now());
+				<Profiles<T>>::insert(&target, profile);
+				Self::deposit_event(Event::ProfileApproved(target, system));
+			} else {
+				Self::deposit_event(Event::ProfileRejected(target, system));
 			}
+			Ok(())
 		}
 
-		fn check_union(who: &RoleId, role1: Role, role2: Role) -> DispatchResult {
-			let account = <Accounts<T>>::get(who).unwrap();
-			match account.role {
-				a if a == role1 => match account.status {
-					RoleStatus::Approved => return Ok(()),
-					RoleStatus::Revoked => Err(Error::<T>::AlreadyRevoked)?,
-					RoleStatus::Pending => Err(Error::<T>::NotApproved)?,
-				},
-				b if b == role2 => match account.status {
-					RoleStatus::Approved => return Ok(()),
-					RoleStatus::Revoked => Err(Error::<T>::AlreadyRevoked)?,
-					RoleStatus::Pending => Err(Error::<T>::NotApproved)?,
-				},
-				_ => Err(Error::<T>::InvalidRole)?,
+		/// publish the `did:vaccine:<RoleId>` document of a role ID, replacing the current one
+		#[pallet::weight(10_000)]
+		pub fn set_did_document(
+			origin: OriginFor<T>,
+			role_id: RoleId,
+			controller: Option<RoleId>,
+			keys: Vec<VerificationKey>,
+			services: Vec<DidService>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_did_controller(&who, &role_id)?;
+			if let Some(controller) = &controller {
+				ensure!(<Accounts<T>>::contains_key(controller), Error::<T>::NotFoundRole);
 			}
+			Self::check_did_entries(&keys, &services)?;
+
+			<DidDocuments<T>>::insert(
+				&role_id,
+				DidDocument { controller, keys, services, updated_at: Self::now() },
+			);
+
+			Self::deposit_event(Event::DidDocumentSet(role_id));
+			Ok(())
 		}
-	}
-}
 
-impl<T: Config> Pallet<T> {
-	pub fn only_system(user: RoleId) -> bool {
-		SystemManager::<T>::get(user).unwrap_or(false)
-	}
-}
+		/// remove the DID document of a role ID, its DID then only lists the bound key
+		#[pallet::weight(10_000)]
+		pub fn remove_did_document(origin: OriginFor<T>, role_id: RoleId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_did_controller(&who, &role_id)?;
+			ensure!(<DidDocuments<T>>::contains_key(&role_id), Error::<T>::NotFoundDidDocument);
 
-pub struct UserId([u8; 36]);
+			<DidDocuments<T>>::remove(&role_id);
 
-#[cfg(feature = "std")]
+			Self::deposit_event(Event::DidDocumentRemoved(role_id));
+			Ok(())
+		}
+
+		/// move a role ID, with everything it owns, to a new signing key
+		#[pallet::weight(10_000)]
+		pub fn rotate_key(
+			origin: OriginFor<T>,
+			role_id: RoleId,
+			new_account: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_owner(&who, &role_id)?;
+			ensure!(!<RoleIdOf<T>>::contains_key(&new_account), Error::<T>::AccountAlreadyBound);
+			// the key may be the lost one, it can not move the role ID away from a recovery
+			ensure!(!<Recoveries<T>>::contains_key(&role_id), Error::<T>::RecoveryInProgress);
+
+			Self::unbind_account(&role_id);
+			Self::bind_account(&role_id, &new_account);
+
+			Self::deposit_event(Event::KeyRotated(role_id, who, new_account));
+			Ok(())
+		}
+
+		/// start moving a role ID whose key was lost to a new key, it can be completed after
+		/// `RecoveryDelay`, the key of a system manager only once the proposal threshold is
+		/// reached
+		#[pallet::weight(10_000)]
+		pub fn initiate_recovery(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+			new_account: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+			// taking over a system manager identity needs the proposal threshold
+			if Self::holds_role(&target, &Role::SYSMAN) {
+				return Self::vote(system, ProposalAction::RecoverSystem { target, new_account })
+			}
+
+			Self::check_recovery(&target, &new_account)?;
+			Self::start_recovery(target, new_account, system);
+			Ok(())
+		}
+
+		/// cancel a key recovery by a system manager, the current key of the role ID only
+		/// disputes it until a system manager cancels or confirms it
+		#[pallet::weight(10_000)]
+		pub fn cancel_recovery(
+			origin: OriginFor<T>,
+			canceller: RoleId,
+			target: RoleId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if canceller == target {
+				Self::check_owner(&who, &target)?;
+			} else {
+				Self::ensure_sysman(&who, &canceller)?;
+			}
+			let mut recovery = <Recoveries<T>>::get(&target).ok_or(Error::<T>::NotFoundRecovery)?;
+
+			// whoever stole the lost key would object as well
+			if canceller == target {
+				recovery.disputed = true;
+				<Recoveries<T>>::insert(&target, recovery);
+				Self::deposit_event(Event::RecoveryDisputed(target));
+				return Ok(())
+			}
+			<Recoveries<T>>::remove(&target);
+			Self::deposit_event(Event::RecoveryCancelled(target, canceller));
+			Ok(())
+		}
+
+		/// let a disputed key recovery go ahead, overriding the objection of the current key, by
+		/// another system manager than the one who started it
+		#[pallet::weight(10_000)]
+		pub fn confirm_recovery(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+
+			<Recoveries<T>>::try_mutate(&target, |recovery| -> DispatchResult {
+				let recovery = recovery.as_mut().ok_or(Error::<T>::NotFoundRecovery)?;
+				ensure!(recovery.disputed, Error::<T>::RecoveryNotDisputed);
+				ensure!(recovery.initiated_by != system, Error::<T>::RecoveryInitiator);
+				recovery.disputed = false;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::RecoveryConfirmed(target, system));
+			Ok(())
+		}
+
+		/// complete a key recovery once the delay passed, signed by the new key
+		#[pallet::weight(10_000)]
+		pub fn complete_recovery(origin: OriginFor<T>, target: RoleId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let recovery = <Recoveries<T>>::get(&target).ok_or(Error::<T>::NotFoundRecovery)?;
+			ensure!(recovery.new_account == who, Error::<T>::NotRoleOwner);
+			ensure!(!recovery.disputed, Error::<T>::RecoveryDisputed);
+			ensure!(Self::now() >= recovery.execute_after, Error::<T>::RecoveryNotReady);
+			ensure!(!<RoleIdOf<T>>::contains_key(&who), Error::<T>::AccountAlreadyBound);
+
+			<Recoveries<T>>::remove(&target);
+			Self::unbind_account(&target);
+			Self::bind_account(&target, &who);
+
+			Self::deposit_event(Event::KeyRecovered(target, who));
+			Ok(())
+		}
+
+		/// lend capabilities of a role ID to an operator key for `duration` milliseconds
+		#[pallet::weight(10_000)]
+		pub fn delegate(
+			origin: OriginFor<T>,
+			principal: RoleId,
+			delegate: T::AccountId,
+			mut capabilities: Vec<Capability>,
+			duration: u64,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_owner(&who, &principal)?;
+			ensure!(delegate != who, Error::<T>::InvalidDelegation);
+			ensure!(
+				!capabilities.is_empty() &&
+					duration > 0 && duration <= T::MaxDelegationPeriod::get(),
+				Error::<T>::InvalidDelegation
+			);
+
+			// only what the principal may do itself
+			for capability in &capabilities {
+				Self::has_permission(&principal, *capability)?;
+			}
+			capabilities.sort();
+			capabilities.dedup();
+
+			let expires_at = Self::now().saturating_add(duration);
+			let delegation = Delegation { capabilities: capabilities.clone(), expires_at };
+			<Delegations<T>>::insert(&principal, &delegate, delegation);
+
+			Self::deposit_event(Event::Delegated(principal, delegate, capabilities, expires_at));
+			Ok(())
+		}
+
+		/// withdraw a delegation, by the key of the principal or the operator key itself
+		#[pallet::weight(10_000)]
+		pub fn revoke_delegation(
+			origin: OriginFor<T>,
+			principal: RoleId,
+			delegate: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if who != delegate {
+				Self::check_owner(&who, &principal)?;
+			}
+			ensure!(
+				<Delegations<T>>::contains_key(&principal, &delegate),
+				Error::<T>::NotFoundDelegation
+			);
+
+			<Delegations<T>>::remove(&principal, &delegate);
+
+			Self::deposit_event(Event::DelegationRevoked(principal, delegate));
+			Ok(())
+		}
+
+		/// ask for the erasure of the personal records of a user account
+		#[pallet::weight(10_000)]
+		pub fn request_erasure(origin: OriginFor<T>, role_id: RoleId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_owner(&who, &role_id)?;
+			Self::ensure_erasable(&role_id)?;
+
+			<ErasureRequests<T>>::insert(&role_id, Self::now());
+
+			Self::deposit_event(Event::ErasureRequested(role_id));
+			Ok(())
+		}
+
+		/// erase a user account on its request, leaving only a tombstone
+		#[pallet::weight(10_000)]
+		pub fn confirm_erasure(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+			ensure!(<ErasureRequests<T>>::contains_key(&target), Error::<T>::NotFoundErasure);
+			// the account may have changed since the request
+			Self::ensure_erasable(&target)?;
+
+			Self::release_role_id(&target);
+			T::OnErasure::on_erasure(&target);
+
+			let tombstone = T::Hashing::hash_of(&target);
+			<Tombstones<T>>::insert(
+				&tombstone,
+				Tombstone { erased_at: Self::now(), confirmed_by: system.clone() },
+			);
+
+			Self::deposit_event(Event::AccountErased(tombstone, system));
+			Ok(())
+		}
+
+		/// vote for adding an admin for special purposes
+		#[pallet::weight(10_000)]
+		pub fn add_system(origin: OriginFor<T>, system: RoleId, user: RoleId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+
+			Self::vote(system, ProposalAction::AddSystem(user))
+		}
+
+		/// vote for removing an admin for special purposes
+		#[pallet::weight(10_000)]
+		pub fn remove_system(origin: OriginFor<T>, system: RoleId, user: RoleId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+
+			Self::vote(system, ProposalAction::RemoveSystem(user))
+		}
+
+		/// vote for replacing the capabilities of a role in the permission table
+		#[pallet::weight(10_000)]
+		pub fn set_role_permissions(
+			origin: OriginFor<T>,
+			system: RoleId,
+			role: Role,
+			mut capabilities: Vec<Capability>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+
+			// identical tables are the same proposal
+			capabilities.sort();
+			capabilities.dedup();
+			Self::vote(system, ProposalAction::SetPermissions { role, capabilities })
+		}
+
+		/// vote for slashing the bond of a role on proven misconduct, the role is revoked
+		#[pallet::weight(10_000)]
+		pub fn slash_bond(
+			origin: OriginFor<T>,
+			system: RoleId,
+			target: RoleId,
+			role: Role,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// only sysman execute
+			Self::ensure_sysman(&who, &system)?;
+
+			Self::vote(system, ProposalAction::SlashBond { target, role })
+		}
+
+		/// set the number of system manager votes a proposal needs
+		#[pallet::weight(10_000)]
+		pub fn set_proposal_threshold(origin: OriginFor<T>, threshold: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(threshold > 0, Error::<T>::InvalidThreshold);
+
+			<ProposalThreshold<T>>::put(threshold);
+			Self::log_admin_change(Vec::new(), AdminChange::ThresholdSet(threshold));
+			Self::deposit_event(Event::ThresholdSet(threshold));
+			Ok(())
+		}
+
+		/// clean up a proposal whose deadline has passed, callable by anyone
+		#[pallet::weight(10_000)]
+		pub fn remove_expired_proposal(origin: OriginFor<T>, proposal: T::Hash) -> DispatchResult {
+			ensure_signed(origin)?;
+			let deadline =
+				<Proposals<T>>::get(&proposal).ok_or(Error::<T>::NotFoundProposal)?.deadline;
+			ensure!(Self::now() >= deadline, Error::<T>::ProposalNotExpired);
+
+			<Proposals<T>>::remove(&proposal);
+			Self::deposit_event(Event::ProposalExpired(proposal));
+			Ok(())
+		}
+
+		/// bind a key to a registered role ID without one, such as the role IDs carried over
+		/// from before keys were bound to them
+		#[pallet::weight(10_000)]
+		pub fn bind_key(
+			origin: OriginFor<T>,
+			role_id: RoleId,
+			account_id: T::AccountId,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(<Accounts<T>>::contains_key(&role_id), Error::<T>::NotFoundRole);
+			ensure!(!<AccountRole<T>>::contains_key(&role_id), Error::<T>::AlreadyRegistered);
+			ensure!(!<RoleIdOf<T>>::contains_key(&account_id), Error::<T>::AccountAlreadyBound);
+
+			Self::bind_account(&role_id, &account_id);
+
+			Self::deposit_event(Event::KeyBound(role_id, account_id));
+			Ok(())
+		}
+	}
+
+	/* ----------------------------------------------helper function
+	 * ------------------------------------------------- */
+	impl<T: Config> AccountPallet<T::AccountId> for Pallet<T> {
+		fn check_owner(who: &T::AccountId, role_id: &RoleId) -> DispatchResult {
+			ensure!(
+				<AccountRole<T>>::get(role_id).as_ref() == Some(who),
+				Error::<T>::NotRoleOwner
+			);
+			Ok(())
+		}
+
+		fn check_claim_account(claimer: &RoleId, role: Role) -> DispatchResult {
+			let status =
+				<Accounts<T>>::get(claimer).and_then(|account| account.roles.get(&role).cloned());
+			match status {
+				None => Ok(()),
+				Some(RoleStatus::Approved) => Err(Error::<T>::AlreadyApproved)?,
+				Some(RoleStatus::Revoked) => Err(Error::<T>::AlreadyRevoked)?,
+				Some(RoleStatus::Pending) => Err(Error::<T>::AlreadyClaimed)?,
+				Some(RoleStatus::Suspended { .. }) => Err(Error::<T>::AccountSuspended)?,
+				// renewal of an expired licence
+				Some(RoleStatus::Expired) => Ok(()),
+			}
+		}
+
+		fn check_account(who: &RoleId, role: Role) -> DispatchResult {
+			let account = <Accounts<T>>::get(who).ok_or(Error::<T>::NotFoundRole)?;
+			match account.roles.get(&role) {
+				Some(status) => Self::check_role(who, &role, status),
+				None => Err(Error::<T>::InvalidRole)?,
+			}
+		}
+
+		fn check_union(who: &RoleId, role1: Role, role2: Role) -> DispatchResult {
+			let account = <Accounts<T>>::get(who).ok_or(Error::<T>::NotFoundRole)?;
+			match (account.roles.get(&role1), account.roles.get(&role2)) {
+				(Some(status1), Some(status2)) => Self::check_role(who, &role1, status1)
+					.or_else(|_| Self::check_role(who, &role2, status2)),
+				(Some(status), None) => Self::check_role(who, &role1, status),
+				(None, Some(status)) => Self::check_role(who, &role2, status),
+				(None, None) => Err(Error::<T>::InvalidRole)?,
+			}
+		}
+
+		fn has_permission(who: &RoleId, capability: Capability) -> Result<RoleId, DispatchError> {
+			match <SubAccounts<T>>::get(who) {
+				Some(sub) => {
+					ensure!(sub.capabilities.contains(&capability), Error::<T>::PermissionDeny);
+					// a sub-account is cut off together with its organisation
+					Self::check_role_capability(&sub.parent, capability)?;
+					Ok(sub.parent)
+				},
+				None => {
+					Self::check_role_capability(who, capability)?;
+					Ok(who.clone())
+				},
+			}
+		}
+
+		fn parent_of(who: &RoleId) -> Option<RoleId> {
+			<SubAccounts<T>>::get(who).map(|sub| sub.parent)
+		}
+
+		fn roles_of(who: &RoleId) -> Vec<Role> {
+			<Accounts<T>>::get(who)
+				.map(|account| account.roles.into_keys().collect())
+				.unwrap_or_default()
+		}
+
+		fn role_status(who: &RoleId, role: Role) -> Option<RoleStatus> {
+			<Accounts<T>>::get(who).and_then(|account| account.roles.get(&role).cloned())
+		}
+
+		fn organisation_of(who: &RoleId) -> RoleId {
+			Self::parent_of(who).unwrap_or_else(|| who.clone())
+		}
+
+		fn jurisdiction_of(who: &RoleId) -> Option<Vec<u8>> {
+			<Profiles<T>>::get(Self::organisation_of(who))
+				.map(|profile| profile.jurisdiction.into())
+		}
+
+		fn account_of(who: &RoleId) -> Option<T::AccountId> {
+			<AccountRole<T>>::get(who)
+		}
+
+		fn check_delegate(
+			who: &T::AccountId,
+			principal: &RoleId,
+			capability: Capability,
+		) -> DispatchResult {
+			// a key without delegation may not sign for the role ID at all
+			let delegation =
+				<Delegations<T>>::get(principal, who).ok_or(Error::<T>::NotRoleOwner)?;
+			ensure!(Self::now() < delegation.expires_at, Error::<T>::DelegationExpired);
+			ensure!(delegation.capabilities.contains(&capability), Error::<T>::PermissionDeny);
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	pub fn only_system(user: RoleId) -> bool {
+		SystemManager::<T>::get(user).unwrap_or(false)
+	}
+
+	/// Whether `role_id` is a system manager whose SYSMAN role is active.
+	pub fn is_active_system(role_id: &RoleId) -> bool {
+		Self::check_account(role_id, Role::SYSMAN).is_ok()
+	}
+
+	/// Only an approved system manager bound to `who` passes.
+	pub fn ensure_sysman(who: &T::AccountId, system: &RoleId) -> DispatchResult {
+		Self::check_owner(who, system)?;
+		ensure!(Self::only_system(system.clone()), Error::<T>::PermissionDeny);
+		Self::check_account(system, Role::SYSMAN)
+	}
+
+	/// Passes if one of the roles of `who` granting `capability` is active.
+	pub fn check_role_capability(who: &RoleId, capability: Capability) -> DispatchResult {
+		let account = <Accounts<T>>::get(who).ok_or(Error::<T>::NotFoundRole)?;
+		let mut result = Err(Error::<T>::InvalidRole.into());
+		for (role, status) in &account.roles {
+			if Self::capabilities_of(role).contains(&capability) {
+				result = Self::check_role(who, role, status);
+				if result.is_ok() {
+					break
+				}
+			}
+		}
+		result
+	}
+
+	/// Capabilities of `role` in the permission table.
+	pub fn capabilities_of(role: &Role) -> Vec<Capability> {
+		<Permissions<T>>::get(role).unwrap_or_else(|| role.default_capabilities().to_vec())
+	}
+
+	/// Sub-accounts of the organisation `parent`.
+	pub fn sub_accounts_of(parent: &RoleId) -> Vec<RoleId> {
+		<SubAccountsOf<T>>::iter_key_prefix(parent).collect()
+	}
+
+	/// Record the vote of `system` for `action`, opening a proposal if there is none, and carry
+	/// the action out once the threshold is reached.
+	fn vote(system: RoleId, action: ProposalAction<T::AccountId>) -> DispatchResult {
+		// a proposal that can never be carried out is refused right away
+		Self::validate_action(&action)?;
+
+		let hash = T::Hashing::hash_of(&action);
+		let now = Self::now();
+		let mut proposal = match <Proposals<T>>::get(&hash) {
+			Some(proposal) if now < proposal.deadline => proposal,
+			stale => {
+				if stale.is_some() {
+					Self::deposit_event(Event::ProposalExpired(hash));
+				}
+				Self::deposit_event(Event::Proposed(hash, system.clone()));
+				Proposal {
+					action: action.clone(),
+					votes: Vec::new(),
+					deadline: now.saturating_add(T::ProposalLifetime::get()),
+				}
+			},
+		};
+		ensure!(!proposal.votes.contains(&system), Error::<T>::AlreadyVoted);
+		proposal.votes.push(system.clone());
+
+		// votes of system managers removed, revoked or suspended in the meantime no longer count
+		let votes =
+			proposal.votes.iter().filter(|voter| Self::is_active_system(voter)).count() as u32;
+		Self::deposit_event(Event::Voted(hash, system, votes));
+
+		// removing oneself always needs the vote of another system manager
+		let lone_self_removal = match &action {
+			ProposalAction::RemoveSystem(user) |
+			ProposalAction::RevokeSystem { target: user, .. } |
+			ProposalAction::SuspendSystem { target: user, .. } =>
+				proposal.votes.iter().all(|voter| voter == user),
+			_ => false,
+		};
+		if votes >= Self::proposal_threshold() && !lone_self_removal {
+			<Proposals<T>>::remove(&hash);
+			Self::execute_action(action, proposal.votes);
+		} else {
+			<Proposals<T>>::insert(&hash, proposal);
+		}
+		Ok(())
+	}
+
+	fn validate_action(action: &ProposalAction<T::AccountId>) -> DispatchResult {
+		match action {
+			ProposalAction::ApproveRole { target, role, valid_for } => {
+				// an expiring system manager could leave nobody to approve the next one
+				ensure!(
+					*role != Role::SYSMAN || valid_for.is_none(),
+					Error::<T>::ExpiringSystemManager
+				);
+				let account = <Accounts<T>>::get(target).ok_or(Error::<T>::NotFoundRole)?;
+				match account.roles.get(role) {
+					Some(RoleStatus::Pending) => Ok(()),
+					Some(_) => Err(Error::<T>::InvalidStatus)?,
+					None => Err(Error::<T>::NotClaimed)?,
+				}
+			},
+			// only an approved system manager role is added back
+			ProposalAction::AddSystem(user) => {
+				let account = <Accounts<T>>::get(user).ok_or(Error::<T>::NotFoundRole)?;
+				let status = account.roles.get(&Role::SYSMAN).ok_or(Error::<T>::InvalidRole)?;
+				Self::check_status(status)
+			},
+			ProposalAction::RemoveSystem(user) => {
+				ensure!(Self::only_system(user.clone()), Error::<T>::NotSystemManager);
+				Self::ensure_other_system(user)
+			},
+			ProposalAction::SetPermissions { .. } => Ok(()),
+			ProposalAction::SlashBond { target, role } => {
+				ensure!(<Bonds<T>>::contains_key(target, role), Error::<T>::NotFoundBond);
+				if *role == Role::SYSMAN {
+					Self::ensure_other_system(target)?;
+				}
+				Ok(())
+			},
+			ProposalAction::RevokeSystem { target, .. } => {
+				Self::check_status_change(target, &Role::SYSMAN, Self::revoked)?;
+				Self::ensure_other_system(target)
+			},
+			ProposalAction::SuspendSystem { target, .. } => {
+				Self::check_status_change(target, &Role::SYSMAN, |status| {
+					Self::suspended(status, 0)
+				})?;
+				Self::ensure_other_system(target)
+			},
+			ProposalAction::ReinstateRole { target, role, .. } =>
+				Self::check_status_change(target, role, Self::reinstated),
+			ProposalAction::RecoverSystem { target, new_account } =>
+				Self::check_recovery(target, new_account),
+		}
+	}
+
+	fn execute_action(action: ProposalAction<T::AccountId>, votes: Vec<RoleId>) {
+		match action {
+			ProposalAction::ApproveRole { target, role, valid_for } => {
+				<Accounts<T>>::mutate(&target, |account| {
+					if let Some(account) = account {
+						account.roles.insert(role.clone(), RoleStatus::Approved);
+					}
+				});
+				<PendingClaims<T>>::remove(&target, &role);
+				match valid_for {
+					Some(duration) =>
+						Self::set_expiry(&target, &role, Self::now().saturating_add(duration)),
+					None => <RoleExpiry<T>>::remove(&target, &role),
+				}
+				if role == Role::SYSMAN {
+					SystemManager::<T>::insert(target.clone(), true);
+					Self::log_admin_change(votes, AdminChange::AddSystem(target.clone()));
+				}
+				Self::deposit_event(Event::Approved(target, role));
+			},
+			ProposalAction::AddSystem(user) => {
+				SystemManager::<T>::insert(&user, true);
+				Self::log_admin_change(votes, AdminChange::AddSystem(user.clone()));
+				Self::deposit_event(Event::AddSystem(user));
+			},
+			ProposalAction::RemoveSystem(user) => {
+				SystemManager::<T>::remove(&user);
+				Self::log_admin_change(votes, AdminChange::RemoveSystem(user.clone()));
+				Self::deposit_event(Event::RemoveSystem(user));
+			},
+			ProposalAction::SetPermissions { role, capabilities } => {
+				<Permissions<T>>::insert(&role, &capabilities);
+				Self::log_admin_change(
+					votes,
+					AdminChange::PermissionsSet(role.clone(), capabilities.clone()),
+				);
+				Self::deposit_event(Event::PermissionsSet(role, capabilities));
+			},
+			ProposalAction::SlashBond { target, role } => {
+				if let Some(bond) = <Bonds<T>>::take(&target, &role) {
+					// the slashed funds are burnt
+					let (_, unslashed) = T::Currency::slash_reserved(&bond.who, bond.amount);
+					<Accounts<T>>::mutate(&target, |account| {
+						if let Some(status) =
+							account.as_mut().and_then(|account| account.roles.get_mut(&role))
+						{
+							*status = RoleStatus::Revoked;
+						}
+					});
+					<PendingClaims<T>>::remove(&target, &role);
+					<RoleExpiry<T>>::remove(&target, &role);
+					Self::log_admin_change(
+						votes,
+						AdminChange::BondSlashed(target.clone(), role.clone()),
+					);
+					Self::deposit_event(Event::BondSlashed(
+						target,
+						role,
+						bond.amount.saturating_sub(unslashed),
+					));
+				}
+			},
+			ProposalAction::RevokeSystem { target, reason } => {
+				if Self::update_status(&target, &Role::SYSMAN, Self::revoked).is_ok() {
+					Self::log_admin_change(
+						votes,
+						AdminChange::SystemRevoked(target.clone(), reason),
+					);
+					Self::deposit_event(Event::Revoked(target, Role::SYSMAN, reason));
+				}
+			},
+			ProposalAction::SuspendSystem { target, duration, reason } => {
+				let until = Self::now().saturating_add(duration);
+				let suspend = |status: &RoleStatus| Self::suspended(status, until);
+				if Self::update_status(&target, &Role::SYSMAN, suspend).is_ok() {
+					Self::log_admin_change(
+						votes,
+						AdminChange::SystemSuspended(target.clone(), until, reason),
+					);
+					Self::deposit_event(Event::Suspended(target, Role::SYSMAN, until, reason));
+				}
+			},
+			ProposalAction::ReinstateRole { target, role, reason } => {
+				if Self::update_status(&target, &role, Self::reinstated).is_ok() {
+					if role == Role::SYSMAN {
+						Self::log_admin_change(
+							votes,
+							AdminChange::SystemReinstated(target.clone(), reason),
+						);
+					}
+					Self::deposit_event(Event::Reinstated(target, role, reason));
+				}
+			},
+			ProposalAction::RecoverSystem { target, new_account } => {
+				// the proposer of the recovery stands for its initiator
+				if let Some(initiated_by) = votes.first() {
+					Self::start_recovery(target, new_account, initiated_by.clone());
+				}
+			},
+		}
+	}
+
+	/// Fails unless an active system manager other than `user` remains.
+	fn ensure_other_system(user: &RoleId) -> DispatchResult {
+		ensure!(
+			SystemManager::<T>::iter_keys()
+				.any(|system| &system != user && Self::is_active_system(&system)),
+			Error::<T>::LastSystemManager
+		);
+		Ok(())
+	}
+
+	fn log_admin_change(who: Vec<RoleId>, change: AdminChange) {
+		let index = <AdminLogCount<T>>::get();
+		let entry = AdminLogEntry {
+			who,
+			change,
+			at: Self::now(),
+			block: <frame_system::Pallet<T>>::block_number(),
+		};
+		<AdminLog<T>>::insert(index, entry);
+		<AdminLogCount<T>>::put(index.saturating_add(1));
+	}
+
+	/// Only an approved role, or one whose suspension has elapsed, is active.
+	pub fn check_status(status: &RoleStatus) -> DispatchResult {
+		match status {
+			RoleStatus::Approved => Ok(()),
+			RoleStatus::Revoked => Err(Error::<T>::AlreadyRevoked)?,
+			RoleStatus::Pending => Err(Error::<T>::NotApproved)?,
+			RoleStatus::Suspended { until } => {
+				ensure!(Self::now() >= *until, Error::<T>::AccountSuspended);
+				Ok(())
+			},
+			RoleStatus::Expired => Err(Error::<T>::RoleExpired)?,
+		}
+	}
+
+	/// Like `check_status`, also rejecting a role whose approval window has passed but which
+	/// has not been swept yet, and the system manager role of a removed system manager.
+	pub fn check_role(role_id: &RoleId, role: &Role, status: &RoleStatus) -> DispatchResult {
+		Self::check_status(status)?;
+		if *role == Role::SYSMAN {
+			ensure!(Self::only_system(role_id.clone()), Error::<T>::NotSystemManager);
+		}
+		if let Some(until) = <RoleExpiry<T>>::get(role_id, role) {
+			ensure!(Self::now() < until, Error::<T>::RoleExpired);
+		}
+		Ok(())
+	}
+
+	/// Record the end of the approval window of `role` and queue it for the sweep.
+	fn set_expiry(role_id: &RoleId, role: &Role, until: u64) {
+		let period = until / T::ExpiryPeriod::get().max(1);
+		<RoleExpiry<T>>::insert(role_id, role, until);
+		<ExpiryQueue<T>>::append(period, (role_id.clone(), role.clone(), until));
+		// a shorter window approved after a longer one is swept first
+		<NextSweepPeriod<T>>::mutate(|next| {
+			*next = Some(next.map_or(period, |next| next.min(period)));
+		});
+	}
+
+	/// Flip every approval whose window ended in an already finished expiry period to `Expired`.
+	pub fn sweep_expired_roles() -> Weight {
+		// bound the work done in a single block when the sweep falls behind
+		const MAX_PERIODS_PER_BLOCK: u64 = 16;
+
+		let mut next = match <NextSweepPeriod<T>>::get() {
+			Some(next) => next,
+			None => return T::DbWeight::get().reads(1),
+		};
+		let current = Self::now() / T::ExpiryPeriod::get().max(1);
+		let mut reads_writes = 1u64;
+		let mut swept = 0u64;
+
+		while next < current && swept < MAX_PERIODS_PER_BLOCK {
+			for (role_id, role, until) in <ExpiryQueue<T>>::take(next) {
+				reads_writes += 2;
+				// the approval was renewed or withdrawn since it was queued
+				if <RoleExpiry<T>>::get(&role_id, &role) != Some(until) {
+					continue
+				}
+
+				let expired = <Accounts<T>>::mutate(&role_id, |account| {
+					match account.as_mut().and_then(|account| account.roles.get_mut(&role)) {
+						Some(status)
+							if matches!(
+								status,
+								RoleStatus::Approved | RoleStatus::Suspended { .. }
+							) =>
+						{
+							*status = RoleStatus::Expired;
+							true
+						},
+						_ => false,
+					}
+				});
+				// a revoked role keeps its end date, reinstating it does not lift the end
+				if expired {
+					<RoleExpiry<T>>::remove(&role_id, &role);
+					Self::deposit_event(Event::RoleExpired(role_id, role));
+				}
+			}
+			next += 1;
+			swept += 1;
+		}
+
+		<NextSweepPeriod<T>>::put(next);
+		T::DbWeight::get().reads_writes(reads_writes + swept, reads_writes + swept)
+	}
+
+	/// Status of a role after a revocation.
+	fn revoked(status: &RoleStatus) -> Result<RoleStatus, DispatchError> {
+		match status {
+			RoleStatus::Revoked => Err(Error::<T>::AlreadyRevoked.into()),
+			RoleStatus::Pending => Err(Error::<T>::NotApproved.into()),
+			RoleStatus::Approved | RoleStatus::Suspended { .. } | RoleStatus::Expired =>
+				Ok(RoleStatus::Revoked),
+		}
+	}
+
+	/// Status of a role after a suspension until `until`.
+	fn suspended(status: &RoleStatus, until: u64) -> Result<RoleStatus, DispatchError> {
+		match status {
+			RoleStatus::Revoked => Err(Error::<T>::AlreadyRevoked.into()),
+			RoleStatus::Pending => Err(Error::<T>::NotApproved.into()),
+			RoleStatus::Expired => Err(Error::<T>::RoleExpired.into()),
+			RoleStatus::Approved | RoleStatus::Suspended { .. } =>
+				Ok(RoleStatus::Suspended { until }),
+		}
+	}
+
+	/// Status of a role after its suspension or revocation is lifted.
+	fn reinstated(status: &RoleStatus) -> Result<RoleStatus, DispatchError> {
+		match status {
+			RoleStatus::Revoked | RoleStatus::Suspended { .. } => Ok(RoleStatus::Approved),
+			_ => Err(Error::<T>::InvalidStatus.into()),
+		}
+	}
+
+	/// Fails unless `f` accepts the status of `role` held by `target`.
+	fn check_status_change(
+		target: &RoleId,
+		role: &Role,
+		f: impl FnOnce(&RoleStatus) -> Result<RoleStatus, DispatchError>,
+	) -> DispatchResult {
+		let account = <Accounts<T>>::get(target).ok_or(Error::<T>::NotFoundRole)?;
+		let status = account.roles.get(role).ok_or(Error::<T>::InvalidRole)?;
+		f(status).map(|_| ())
+	}
+
+	/// Apply `f` to the status of `role` held by `target`.
+	fn update_status(
+		target: &RoleId,
+		role: &Role,
+		f: impl FnOnce(&RoleStatus) -> Result<RoleStatus, DispatchError>,
+	) -> DispatchResult {
+		<Accounts<T>>::try_mutate(target, |account| -> DispatchResult {
+			let account = account.as_mut().ok_or(Error::<T>::NotFoundRole)?;
+			let status = account.roles.get_mut(role).ok_or(Error::<T>::InvalidRole)?;
+			*status = f(status)?;
+			Ok(())
+		})
+	}
+
+	/// Current unix time in milliseconds.
+	pub fn now() -> u64 {
+		T::UnixTime::now().as_millis().saturated_into::<u64>()
+	}
+
+	/// Bind `role_id` to the key that is allowed to act on its behalf.
+	pub fn bind_account(role_id: &RoleId, account_id: &T::AccountId) {
+		<AccountRole<T>>::insert(role_id, account_id);
+		<RoleIdOf<T>>::insert(account_id, role_id);
+	}
+
+	/// Remove the key binding of `role_id`.
+	pub fn unbind_account(role_id: &RoleId) {
+		if let Some(account_id) = <AccountRole<T>>::take(role_id) {
+			<RoleIdOf<T>>::remove(account_id);
+		}
+	}
+
+	/// Whether `who` is the key of a role ID holding an active role, or of a sub-account of one.
+	pub fn is_approved_actor(who: &T::AccountId) -> bool {
+		let role_id = match <RoleIdOf<T>>::get(who) {
+			Some(role_id) => role_id,
+			None => return false,
+		};
+		let role_id = Self::parent_of(&role_id).unwrap_or(role_id);
+		<Accounts<T>>::get(&role_id).map_or(false, |account| {
+			account
+				.roles
+				.iter()
+				.any(|(role, status)| Self::check_role(&role_id, role, status).is_ok())
+		})
+	}
+
+	/// Fee-free transactions `who` has left in the current period.
+	pub fn free_transactions_left(who: &T::AccountId) -> u32 {
+		match <FreeTransactionsUsed<T>>::get(who) {
+			Some((period, used)) if period == Self::fee_period() =>
+				T::FreeTransactions::get().saturating_sub(used),
+			_ => T::FreeTransactions::get(),
+		}
+	}
+
+	/// Whether a transaction of `who` would be sent free of fees.
+	pub fn can_send_free(who: &T::AccountId) -> bool {
+		Self::free_transactions_left(who) > 0 && Self::is_approved_actor(who)
+	}
+
+	/// Count a fee-free transaction of `who`, false if the transaction has to pay the fee.
+	pub fn use_free_transaction(who: &T::AccountId) -> bool {
+		if !Self::can_send_free(who) {
+			return false
+		}
+		let current = Self::fee_period();
+		<FreeTransactionsUsed<T>>::mutate(who, |used| match used {
+			Some((period, count)) if *period == current => *count += 1,
+			_ => *used = Some((current, 1)),
+		});
+		true
+	}
+
+	fn fee_period() -> T::BlockNumber {
+		<frame_system::Pallet<T>>::block_number() / T::FreeTransactionPeriod::get().max(1u32.into())
+	}
+
+	/// Reserve the bond of an organisation role from `who`, a renewed role keeps its bond.
+	fn reserve_bond(role_id: &RoleId, role: &Role, who: &T::AccountId) -> DispatchResult {
+		if !matches!(role, Role::VM | Role::VAO | Role::VAD) ||
+			<Bonds<T>>::contains_key(role_id, role)
+		{
+			return Ok(())
+		}
+		let amount = T::RoleBond::get();
+		T::Currency::reserve(who, amount)?;
+		<Bonds<T>>::insert(role_id, role, Bond { who: who.clone(), amount });
+		Self::deposit_event(Event::BondReserved(role_id.clone(), role.clone(), amount));
+		Ok(())
+	}
+
+	/// Whether `role_id` holds `role`, whatever its status.
+	fn holds_role(role_id: &RoleId, role: &Role) -> bool {
+		<Accounts<T>>::get(role_id).map_or(false, |account| account.roles.contains_key(role))
+	}
+
+	/// Fails unless the key of `target` can be recovered to `new_account`.
+	fn check_recovery(target: &RoleId, new_account: &T::AccountId) -> DispatchResult {
+		ensure!(<AccountRole<T>>::contains_key(target), Error::<T>::NotFoundRole);
+		ensure!(!<Recoveries<T>>::contains_key(target), Error::<T>::RecoveryInProgress);
+		ensure!(!<RoleIdOf<T>>::contains_key(new_account), Error::<T>::AccountAlreadyBound);
+		Ok(())
+	}
+
+	/// Open the recovery of the key of `target` to `new_account`.
+	fn start_recovery(target: RoleId, new_account: T::AccountId, initiated_by: RoleId) {
+		let execute_after = Self::now().saturating_add(T::RecoveryDelay::get());
+		let recovery =
+			Recovery { new_account: new_account.clone(), initiated_by, execute_after, disputed: false };
+		<Recoveries<T>>::insert(&target, recovery);
+
+		Self::deposit_event(Event::RecoveryInitiated(target, new_account, execute_after));
+	}
+
+	/// Whether `role` is the only role `role_id` holds.
+	fn is_last_role(role_id: &RoleId, role: &Role) -> bool {
+		<Accounts<T>>::get(role_id)
+			.map_or(false, |account| account.roles.len() == 1 && account.roles.contains_key(role))
+	}
+
+	/// Fails while a sub-account of `role_id` can not be removed with it.
+	fn ensure_releasable(role_id: &RoleId) -> DispatchResult {
+		for sub in <SubAccountsOf<T>>::iter_key_prefix(role_id) {
+			T::OnSubAccountRemoval::can_remove(&sub)?;
+		}
+		Ok(())
+	}
+
+	/// Remove the account of `role_id` with everything kept under it, so that whoever holds the
+	/// role ID next starts afresh.
+	fn release_role_id(role_id: &RoleId) {
+		<Accounts<T>>::remove(role_id);
+		Self::unbind_account(role_id);
+		let subs: Vec<RoleId> = <SubAccountsOf<T>>::iter_key_prefix(role_id).collect();
+		for sub in subs {
+			<SubAccounts<T>>::remove(&sub);
+			<SubAccountsOf<T>>::remove(role_id, &sub);
+			Self::unbind_account(&sub);
+			Self::deposit_event(Event::SubAccountRemoved(role_id.clone(), sub));
+		}
+		let roles: Vec<Role> = <RoleExpiry<T>>::iter_key_prefix(role_id).collect();
+		for role in roles {
+			<RoleExpiry<T>>::remove(role_id, &role);
+		}
+		let delegates: Vec<T::AccountId> = <Delegations<T>>::iter_key_prefix(role_id).collect();
+		for delegate in delegates {
+			<Delegations<T>>::remove(role_id, &delegate);
+		}
+		<Recoveries<T>>::remove(role_id);
+		<Profiles<T>>::remove(role_id);
+		<PendingProfiles<T>>::remove(role_id);
+		<DidDocuments<T>>::remove(role_id);
+		<ErasureRequests<T>>::remove(role_id);
+	}
+
+	/// Return the bond of a role to the key it was reserved from.
+	fn release_bond(role_id: &RoleId, role: &Role) {
+		if let Some(bond) = <Bonds<T>>::take(role_id, role) {
+			T::Currency::unreserve(&bond.who, bond.amount);
+			Self::deposit_event(Event::BondReleased(role_id.clone(), role.clone(), bond.amount));
+		}
+	}
+
+	/// Passes if `who` owns `role_id` or the controller of its DID document.
+	fn ensure_did_controller(who: &T::AccountId, role_id: &RoleId) -> DispatchResult {
+		ensure!(<Accounts<T>>::contains_key(role_id), Error::<T>::NotFoundRole);
+		if Self::check_owner(who, role_id).is_ok() {
+			return Ok(())
+		}
+		match <DidDocuments<T>>::get(role_id).and_then(|document| document.controller) {
+			Some(controller) => Self::check_owner(who, &controller),
+			None => Err(Error::<T>::NotRoleOwner)?,
+		}
+	}
+
+	/// Check the keys and services of a DID document against the limits and their schemes.
+	fn check_did_entries(keys: &[VerificationKey], services: &[DidService]) -> DispatchResult {
+		let max_entries = T::MaxDidEntries::get() as usize;
+		ensure!(
+			keys.len() <= max_entries && services.len() <= max_entries,
+			Error::<T>::TooManyDidEntries
+		);
+
+		let max_length = T::MaxDidLength::get() as usize;
+		let fields = keys.iter().flat_map(|key| [&key.id, &key.public_key]).chain(
+			services
+				.iter()
+				.flat_map(|service| [&service.id, &service.service_type, &service.endpoint]),
+		);
+		for field in fields {
+			ensure!(field.len() <= max_length, Error::<T>::DidFieldTooLong);
+		}
+
+		// the fragment of the bound key is taken
+		let mut ids = Vec::from([ACCOUNT_KEY_ID]);
+		for id in keys.iter().map(|key| &key.id).chain(services.iter().map(|service| &service.id)) {
+			ensure!(!id.is_empty() && !ids.contains(&id.as_slice()), Error::<T>::InvalidDidEntry);
+			ids.push(id.as_slice());
+		}
+		ensure!(
+			keys.iter().all(|key| key.public_key.len() == key.key_type.public_key_len()),
+			Error::<T>::InvalidDidEntry
+		);
+		Ok(())
+	}
+
+	/// What `did:vaccine:<role_id>` resolves to, `None` when no key is bound to the role ID.
+	pub fn resolve_did(role_id: &RoleId) -> Option<DidResolution<T::AccountId>> {
+		let account = <AccountRole<T>>::get(role_id)?;
+		Some(DidResolution { account, document: <DidDocuments<T>>::get(role_id) })
+	}
+
+	/// Passes if `role_id` is a plain user account.
+	fn ensure_erasable(role_id: &RoleId) -> DispatchResult {
+		let account = <Accounts<T>>::get(role_id).ok_or(Error::<T>::NotFoundRole)?;
+		// organisations keep their records, they are part of the supply chain
+		ensure!(account.roles.keys().all(|role| *role == Role::USER), Error::<T>::NotErasable);
+		Ok(())
+	}
+
+	/// Whether `role_id` belonged to an erased account.
+	pub fn is_erased(role_id: &RoleId) -> bool {
+		<Tombstones<T>>::contains_key(T::Hashing::hash_of(role_id))
+	}
+
+	/// All claims waiting for a system manager review.
+	pub fn claims_to_review() -> Vec<(RoleId, Claim)> {
+		<PendingClaims<T>>::iter().map(|(role_id, _, claim)| (role_id, claim)).collect()
+	}
+}
+
+/// Length of a UUID in its canonical text form.
+pub const USER_ID_LEN: usize = 36;
+
+/// Role ID in the canonical lowercase UUID form `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`.
+///
+/// Malformed identifiers are rejected when decoding, so they never reach dispatch or storage.
+#[derive(Encode, Clone, Eq, PartialEq, Ord, PartialOrd, TypeInfo)]
+pub struct UserId([u8; USER_ID_LEN]);
+
+impl UserId {
+	/// Whether `bytes` is a UUID in the canonical lowercase text form.
+	pub fn is_valid(bytes: &[u8]) -> bool {
+		bytes.len() == USER_ID_LEN &&
+			bytes.iter().enumerate().all(|(i, b)| match i {
+				8 | 13 | 18 | 23 => *b == b'-',
+				_ => matches!(b, b'0'..=b'9' | b'a'..=b'f'),
+			})
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl Default for UserId {
+	/// The nil UUID.
+	fn default() -> Self {
+		let mut bytes = [b'0'; USER_ID_LEN];
+		for i in [8, 13, 18, 23] {
+			bytes[i] = b'-';
+		}
+		UserId(bytes)
+	}
+}
+
+impl Decode for UserId {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let bytes = <[u8; USER_ID_LEN]>::decode(input)?;
+		UserId::try_from(bytes).map_err(|_| "Invalid UUID for UserId".into())
+	}
+}
+
+impl TryFrom<[u8; USER_ID_LEN]> for UserId {
+	type Error = ();
+
+	fn try_from(bytes: [u8; USER_ID_LEN]) -> Result<Self, Self::Error> {
+		if UserId::is_valid(&bytes) {
+			Ok(UserId(bytes))
+		} else {
+			Err(())
+		}
+	}
+}
+
+impl TryFrom<&[u8]> for UserId {
+	type Error = ();
+
+	fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; USER_ID_LEN]>::try_from(bytes).map_err(|_| ())?.try_into()
+	}
+}
+
+impl TryFrom<&str> for UserId {
+	type Error = ();
+
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		s.as_bytes().try_into()
+	}
+}
+
+impl sp_std::fmt::Debug for UserId {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		// always valid ASCII
+		write!(f, "UserId({})", sp_std::str::from_utf8(&self.0).unwrap_or_default())
+	}
+}
+
+#[cfg(feature = "std")]
 impl Serialize for UserId {
 	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
 		std::str::from_utf8(&self.0)
@@ -305,20 +2281,7 @@ impl<'de> Deserialize<'de> for UserId {
 		D: Deserializer<'de>,
 	{
 		let s = String::deserialize(deserializer)?;
-
-		let bytes: [u8; 36] = s.try_into().expect("Slice with incorrect length");
-
-		Ok(UserId::from(bytes))
-		// Ok(String::deserialize(deserializer)?
-		// 	.as_bytes()
-		// 	.try_into()
-		// 	.expect("Slice with incorrect length"))
-	}
-}
-
-#[cfg(feature = "std")]
-impl From<[u8; 36]> for UserId {
-	fn from(item: [u8; 36]) -> Self {
-		UserId(item)
+		UserId::try_from(s.as_str())
+			.map_err(|_| D::Error::custom(format!("Invalid UUID for UserId: {}", s)))
 	}
 }
@@ -0,0 +1,99 @@
+//! Storage migrations for the account pallet.
+
+use super::*;
+use frame_support::{
+	migration::storage_key_iter,
+	traits::{PalletInfoAccess, StorageVersion},
+	weights::Weight,
+	Blake2_128, StorageHasher,
+};
+
+/// From the baseline layout, where a role ID was any byte string and each account held a single
+/// role, to storage version 1.
+///
+/// Every role ID is re-keyed to [`role_id_of`] its legacy identifier, so the mapping can be
+/// recomputed off-chain from the identifiers held by the organisations. No key was bound to a
+/// role ID before, root binds them with `bind_key` once the upgrade is enacted, the keys of the
+/// system managers first.
+pub mod v1 {
+	use super::*;
+
+	/// Role ID before storage version 1.
+	pub type OldRoleId = Vec<u8>;
+
+	/// `Account` layout before storage version 1.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+	pub struct OldAccount {
+		pub role: Role,
+		pub status: RoleStatus,
+	}
+
+	/// Role ID of the legacy identifier `old`.
+	///
+	/// A legacy identifier that is already a UUID keeps its value in lowercase, any other maps to
+	/// the UUID made of its blake2 hash, with the version and variant bits of a custom UUID.
+	pub fn role_id_of(old: &[u8]) -> RoleId {
+		if let Ok(role_id) = RoleId::try_from(&old.to_ascii_lowercase()[..]) {
+			return role_id
+		}
+
+		let mut hash = Blake2_128::hash(old);
+		hash[6] = (hash[6] & 0x0f) | 0x80;
+		hash[8] = (hash[8] & 0x3f) | 0x80;
+
+		const HEX: &[u8; 16] = b"0123456789abcdef";
+		let mut bytes = [b'-'; USER_ID_LEN];
+		let mut pos = 0;
+		for (i, byte) in hash.iter().enumerate() {
+			// skip the dashes before the 5th, 7th, 9th and 11th byte
+			if matches!(i, 4 | 6 | 8 | 10) {
+				pos += 1;
+			}
+			bytes[pos] = HEX[(byte >> 4) as usize];
+			bytes[pos + 1] = HEX[(byte & 0x0f) as usize];
+			pos += 2;
+		}
+		UserId(bytes)
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::get::<Pallet<T>>() >= StorageVersion::new(1) {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		let mut translated = 0u64;
+
+		let accounts: Vec<(OldRoleId, OldAccount)> =
+			storage_key_iter::<OldRoleId, OldAccount, Blake2_128Concat>(pallet, b"Accounts")
+				.drain()
+				.collect();
+		for (old_id, old) in accounts {
+			translated += 1;
+			let role_id = role_id_of(&old_id);
+			if role_id.as_bytes() != old_id.as_slice() {
+				log::info!("pallet_account: role ID {:?} is now {:?}", old_id, role_id);
+			}
+			// pending roles were waiting for a system manager in the account itself
+			if old.status == RoleStatus::Pending {
+				let claim = Claim { role: old.role.clone(), claimed_at: 0 };
+				<PendingClaims<T>>::insert(&role_id, &old.role, claim);
+			}
+			<Accounts<T>>::insert(&role_id, Account::new(old.role, old.status));
+		}
+
+		let managers: Vec<(OldRoleId, bool)> =
+			storage_key_iter::<OldRoleId, bool, Twox64Concat>(pallet, b"SystemManager")
+				.drain()
+				.collect();
+		for (old_id, active) in managers {
+			translated += 1;
+			SystemManager::<T>::insert(role_id_of(&old_id), active);
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("pallet_account: migrated {} entries to storage version 1", translated);
+
+		T::DbWeight::get().reads_writes(translated + 1, 2 * translated + 1)
+	}
+}
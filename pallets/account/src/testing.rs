@@ -0,0 +1,181 @@
+//! In-memory `AccountPallet` for the mock runtimes of pallets which depend on accounts, so that
+//! they can be tested without pulling in the account pallet and setting up its state through
+//! extrinsics.
+//!
+//! The accounts live in a thread local and every test runs on its own thread, so tests start
+//! from an empty set of accounts. Keys are `u64` like in the usual mock runtimes.
+
+use crate::{AccountPallet, Capability, Role, RoleId, RoleStatus};
+use frame_support::dispatch::DispatchResult;
+use sp_runtime::DispatchError;
+use std::{cell::RefCell, collections::BTreeMap};
+
+#[derive(Default)]
+struct State {
+	roles: BTreeMap<RoleId, BTreeMap<Role, RoleStatus>>,
+	keys: BTreeMap<RoleId, u64>,
+	// sub-account => (parent, capabilities)
+	sub_accounts: BTreeMap<RoleId, (RoleId, Vec<Capability>)>,
+	jurisdictions: BTreeMap<RoleId, Vec<u8>>,
+	delegations: BTreeMap<(RoleId, u64), Vec<Capability>>,
+}
+
+thread_local! {
+	static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// `AccountPallet` answering from accounts set up directly by the test.
+///
+/// Roles grant their default capabilities and errors carry the names of the errors of the
+/// account pallet. There is no clock: a suspension never lapses and delegations do not expire.
+pub struct MockAccounts;
+
+impl MockAccounts {
+	/// Bind `key` to `role_id` and give it `role` with `status`.
+	pub fn set_role(role_id: &RoleId, key: u64, role: Role, status: RoleStatus) {
+		STATE.with(|state| {
+			let mut state = state.borrow_mut();
+			state.roles.entry(role_id.clone()).or_default().insert(role, status);
+			state.keys.insert(role_id.clone(), key);
+		});
+	}
+
+	/// Bind `key` to the sub-account `sub` of `parent`, acting with `capabilities`.
+	pub fn add_sub_account(parent: &RoleId, sub: &RoleId, key: u64, capabilities: Vec<Capability>) {
+		STATE.with(|state| {
+			let mut state = state.borrow_mut();
+			state.keys.insert(sub.clone(), key);
+			state.sub_accounts.insert(sub.clone(), (parent.clone(), capabilities));
+		});
+	}
+
+	/// Record `jurisdiction` in the approved profile of `role_id`.
+	pub fn set_jurisdiction(role_id: &RoleId, jurisdiction: &[u8]) {
+		STATE.with(|state| {
+			state.borrow_mut().jurisdictions.insert(role_id.clone(), jurisdiction.to_vec());
+		});
+	}
+
+	/// Lend `capabilities` of `principal` to the operator key `key`.
+	pub fn delegate(principal: &RoleId, key: u64, capabilities: Vec<Capability>) {
+		STATE.with(|state| {
+			state.borrow_mut().delegations.insert((principal.clone(), key), capabilities);
+		});
+	}
+
+	fn check_status(status: &RoleStatus) -> DispatchResult {
+		match status {
+			RoleStatus::Approved => Ok(()),
+			RoleStatus::Revoked => Err(DispatchError::Other("AlreadyRevoked")),
+			RoleStatus::Pending => Err(DispatchError::Other("NotApproved")),
+			RoleStatus::Suspended { .. } => Err(DispatchError::Other("AccountSuspended")),
+			RoleStatus::Expired => Err(DispatchError::Other("RoleExpired")),
+		}
+	}
+
+	fn roles(who: &RoleId) -> Result<BTreeMap<Role, RoleStatus>, DispatchError> {
+		STATE
+			.with(|state| state.borrow().roles.get(who).cloned())
+			.ok_or(DispatchError::Other("NotFoundRole"))
+	}
+
+	fn check_role_capability(who: &RoleId, capability: Capability) -> DispatchResult {
+		let mut result = Err(DispatchError::Other("InvalidRole"));
+		for (role, status) in Self::roles(who)? {
+			if role.default_capabilities().contains(&capability) {
+				result = Self::check_status(&status);
+				if result.is_ok() {
+					break
+				}
+			}
+		}
+		result
+	}
+}
+
+impl AccountPallet<u64> for MockAccounts {
+	fn check_owner(who: &u64, role_id: &RoleId) -> DispatchResult {
+		match Self::account_of(role_id) {
+			Some(key) if key == *who => Ok(()),
+			_ => Err(DispatchError::Other("NotRoleOwner")),
+		}
+	}
+
+	fn check_claim_account(claimer: &RoleId, role: Role) -> DispatchResult {
+		match Self::role_status(claimer, role) {
+			None | Some(RoleStatus::Expired) => Ok(()),
+			Some(RoleStatus::Approved) => Err(DispatchError::Other("AlreadyApproved")),
+			Some(RoleStatus::Revoked) => Err(DispatchError::Other("AlreadyRevoked")),
+			Some(RoleStatus::Pending) => Err(DispatchError::Other("AlreadyClaimed")),
+			Some(RoleStatus::Suspended { .. }) => Err(DispatchError::Other("AccountSuspended")),
+		}
+	}
+
+	fn check_account(who: &RoleId, role: Role) -> DispatchResult {
+		match Self::roles(who)?.get(&role) {
+			Some(status) => Self::check_status(status),
+			None => Err(DispatchError::Other("InvalidRole")),
+		}
+	}
+
+	fn check_union(who: &RoleId, role1: Role, role2: Role) -> DispatchResult {
+		let roles = Self::roles(who)?;
+		match (roles.get(&role1), roles.get(&role2)) {
+			(Some(status1), Some(status2)) =>
+				Self::check_status(status1).or_else(|_| Self::check_status(status2)),
+			(Some(status), None) | (None, Some(status)) => Self::check_status(status),
+			(None, None) => Err(DispatchError::Other("InvalidRole")),
+		}
+	}
+
+	fn has_permission(who: &RoleId, capability: Capability) -> Result<RoleId, DispatchError> {
+		match STATE.with(|state| state.borrow().sub_accounts.get(who).cloned()) {
+			Some((parent, capabilities)) => {
+				if !capabilities.contains(&capability) {
+					return Err(DispatchError::Other("PermissionDeny"))
+				}
+				Self::check_role_capability(&parent, capability)?;
+				Ok(parent)
+			},
+			None => {
+				Self::check_role_capability(who, capability)?;
+				Ok(who.clone())
+			},
+		}
+	}
+
+	fn parent_of(who: &RoleId) -> Option<RoleId> {
+		STATE.with(|state| state.borrow().sub_accounts.get(who).map(|(parent, _)| parent.clone()))
+	}
+
+	fn roles_of(who: &RoleId) -> Vec<Role> {
+		Self::roles(who).map(|roles| roles.into_keys().collect()).unwrap_or_default()
+	}
+
+	fn role_status(who: &RoleId, role: Role) -> Option<RoleStatus> {
+		Self::roles(who).ok().and_then(|roles| roles.get(&role).cloned())
+	}
+
+	fn organisation_of(who: &RoleId) -> RoleId {
+		Self::parent_of(who).unwrap_or_else(|| who.clone())
+	}
+
+	fn jurisdiction_of(who: &RoleId) -> Option<Vec<u8>> {
+		let org = Self::organisation_of(who);
+		STATE.with(|state| state.borrow().jurisdictions.get(&org).cloned())
+	}
+
+	fn account_of(who: &RoleId) -> Option<u64> {
+		STATE.with(|state| state.borrow().keys.get(who).copied())
+	}
+
+	fn check_delegate(who: &u64, principal: &RoleId, capability: Capability) -> DispatchResult {
+		let capabilities = STATE
+			.with(|state| state.borrow().delegations.get(&(principal.clone(), *who)).cloned())
+			.ok_or(DispatchError::Other("NotRoleOwner"))?;
+		if !capabilities.contains(&capability) {
+			return Err(DispatchError::Other("PermissionDeny"))
+		}
+		Ok(())
+	}
+}
@@ -1,15 +1,16 @@
 use crate as pallet_account;
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok, traits::GenesisBuild};
+use crate::{
+	mock::*, AccountPallet, AdminChange, AdminLogEntry, Claim, Error, RoleStatus, Tombstone,
+};
+use frame_support::{assert_noop, assert_ok};
 use pallet_account::Role;
+use sp_runtime::DispatchError;
 
 pub const ALICE: u64 = 1;
 pub const BOB: u64 = 2;
 pub const CHARLIE: u64 = 3;
 pub const DAVE: u64 = 4;
 pub const EVE: u64 = 5;
-pub const FRANK: u64 = 6;
-pub const GEORGE: u64 = 7;
 
 // ALICE(1): system manager
 // BOB(2): manufacture
@@ -20,37 +21,46 @@ pub const GEORGE: u64 = 7;
 #[test]
 fn start_system() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// register account
-		assert_ok!(Account::register(Origin::signed(BOB)));
-		assert_ok!(Account::register(Origin::signed(CHARLIE)));
-		assert_ok!(Account::register(Origin::signed(DAVE)));
-		assert_ok!(Account::register(Origin::signed(EVE)));
-		// 2 claimed to be manufacture
-		assert_ok!(Account::claim_role(Origin::signed(BOB), Role::VM));
-		// 3 claimed to be authorized organization
-		assert_ok!(Account::claim_role(Origin::signed(CHARLIE), Role::VAO));
-		// 4 claimed to be distributer
-		assert_ok!(Account::claim_role(Origin::signed(DAVE), Role::VAD));
+		// 1 is system manager by default
+		assert!(Account::only_system(role_id(ALICE)));
+		assert_eq!(Account::account_role(role_id(ALICE)), Some(ALICE));
+		// register accounts
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
+		assert_ok!(Account::register_account(Origin::root(), role_id(CHARLIE), CHARLIE, Role::VAO));
+		assert_ok!(Account::register_account(Origin::root(), role_id(DAVE), DAVE, Role::VAD));
+		assert_ok!(Account::register_account(Origin::root(), role_id(EVE), EVE, Role::USER));
+		// registration binds the role ID to the signing account
+		assert_eq!(Account::account_role(role_id(BOB)), Some(BOB));
+		assert_eq!(Account::role_id_of(BOB), Some(role_id(BOB)));
 		// 2 is manufacture
-		assert_ok!(Account::approve_role(Origin::signed(ALICE), BOB, Role::VM));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
 		// 3 is authorized organization
-		assert_ok!(Account::approve_role(Origin::signed(ALICE), CHARLIE, Role::VAO));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::VAO,
+			None
+		));
 		// 4 is distributer
-		assert_ok!(Account::approve_role(Origin::signed(ALICE), DAVE, Role::VAD));
-		// VM(2) register vaccine information
-		assert_ok!(pallet_vaccine::register_vac_info(Origin::signed(BOB), Some(1)));
-		// VAO approve vaccine
-		assert_ok!(Account::approve_vaccine(Origin::signed(CHARLIE), Some(1)));
-		// VM transfer vaccine to VAD
-		assert_ok!(Account::transfer_vaccine(Origin::signed(BOB), DAVE, Some(1)));
-		// VAD receive vaccine from VM
-		assert_ok!(Account::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)));
-		// User have a vaccine in VAD
-		assert_ok!(Account::transfer_get_vaccine_right(Origin::signed(DAVE), EVE, Some(1)));
-		assert_ok!(Account::confirm_vaccine(Origin::signed(EVE), Some(DAVE), Some(1)));
-
-		println!("vaccine info: {:?}", Account::vaccines(1));
-		println!("vaccine passport info: {:?}", Account::vaccine_passports(EVE));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
+
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Approved)
+		);
 	});
 }
 
@@ -61,283 +71,2394 @@ fn start_system() {
 #[test]
 fn should_cause_error_for_register_role() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(Account::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(Account::claim_vm(Origin::signed(BOB)));
+		// only root registers accounts
+		assert_noop!(
+			Account::register_account(Origin::signed(ALICE), role_id(BOB), BOB, Role::VM),
+			DispatchError::BadOrigin
+		);
 
-		// claim again after claimed but before approved
-		assert_noop!(Account::claim_vm(Origin::signed(BOB)), Error::<Test>::AlreadyClaimed);
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
 
-		// 2 is manufacture
-		assert_ok!(Account::approve_vm(Origin::signed(ALICE), BOB));
+		// register the same role twice
+		assert_noop!(
+			Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM),
+			Error::<Test>::AlreadyRegistered
+		);
+		// add a role to a role ID bound to another key
+		assert_noop!(
+			Account::register_account(Origin::root(), role_id(BOB), CHARLIE, Role::VAD),
+			Error::<Test>::NotRoleOwner
+		);
 
-		// claim again after approved
-		assert_noop!(Account::claim_vm(Origin::signed(BOB)), Error::<Test>::AlreadyApproved);
+		// bind a key which already acts for another role ID
+		assert_noop!(
+			Account::register_account(Origin::root(), role_id(CHARLIE), BOB, Role::USER),
+			Error::<Test>::AccountAlreadyBound
+		);
 
-		// not system manager approve role
-		assert_noop!(Account::approve_vm(Origin::signed(CHARLIE), BOB), Error::<Test>::NotSysMan);
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
 
 		// approve again after approved
-		assert_noop!(Account::approve_vm(Origin::signed(ALICE), BOB), Error::<Test>::NotClaimed);
-		// approve entity which does not claim
 		assert_noop!(
-			Account::approve_vm(Origin::signed(ALICE), CHARLIE),
-			Error::<Test>::NotClaimed
+			Account::approve_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(BOB),
+				Role::VM,
+				None
+			),
+			Error::<Test>::InvalidStatus
+		);
+	});
+}
+
+// ALICE(1): system manager
+// BOB(2): manufacture
+// CHARLIE(3): system manager candidate
+
+#[test]
+fn should_reject_impersonation_of_role_id() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
+		assert_ok!(Account::register_account(
+			Origin::root(),
+			role_id(CHARLIE),
+			CHARLIE,
+			Role::SYSMAN
+		));
+
+		// BOB claims to be the system manager
+		assert_noop!(
+			Account::approve_role(
+				Origin::signed(BOB),
+				role_id(ALICE),
+				role_id(BOB),
+				Role::VM,
+				None
+			),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_noop!(
+			Account::add_system(Origin::signed(BOB), role_id(ALICE), role_id(BOB)),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_noop!(
+			Account::remove_system(Origin::signed(BOB), role_id(ALICE), role_id(ALICE)),
+			Error::<Test>::NotRoleOwner
+		);
+		// unknown key claims a registered role ID
+		assert_noop!(
+			Account::approve_role(
+				Origin::signed(EVE),
+				role_id(ALICE),
+				role_id(CHARLIE),
+				Role::VAO,
+				None
+			),
+			Error::<Test>::NotRoleOwner
 		);
+
+		// the bound key is accepted
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			None
+		));
+		assert!(Account::only_system(role_id(CHARLIE)));
 	});
 }
 
 // ALICE(1): system manager
 // BOB(2): manufacture
 // CHARLIE(3): VAO
-// DAVE(4): user
+// DAVE(4): VAD
+
 #[test]
-fn should_cause_error_for_register_info_about_vaccine() {
+fn should_claim_and_review_role() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(Account::sys_man(ALICE), true);
+		Timestamp::set_timestamp(1_000);
 		// 2 claimed to be manufacture
-		assert_ok!(Account::claim_vm(Origin::signed(BOB)));
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
 		// 3 claimed to be authorized organization
-		assert_ok!(Account::claim_vao(Origin::signed(CHARLIE)));
+		assert_ok!(Account::claim_role(Origin::signed(CHARLIE), role_id(CHARLIE), Role::VAO));
+		// 4 claimed to be distributer
+		assert_ok!(Account::claim_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD));
+
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Pending)
+		);
+		assert_eq!(Account::account_role(role_id(BOB)), Some(BOB));
+		assert_eq!(
+			Account::pending_claims(role_id(BOB), Role::VM),
+			Some(Claim { role: Role::VM, claimed_at: 1_000 })
+		);
+		assert_eq!(Account::claims_to_review().len(), 3);
+
 		// 2 is manufacture
-		assert_ok!(Account::approve_vm(Origin::signed(ALICE), BOB));
-		// 3 is authorized organization
-		assert_ok!(Account::approve_vao(Origin::signed(ALICE), CHARLIE));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Approved)
+		);
+		assert_eq!(Account::pending_claims(role_id(BOB), Role::VM), None);
+		// approving an organisation does not make it a system manager
+		assert!(!Account::only_system(role_id(BOB)));
 
-		// NOT sysman register vaccine type
-		assert_noop!(Account::register_vac_type(Origin::signed(DAVE)), Error::<Test>::NotSysMan);
+		// 3 is rejected and the role ID is released
+		assert_ok!(Account::reject_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::VAO
+		));
+		assert_eq!(Account::accounts(role_id(CHARLIE)), None);
+		assert_eq!(Account::account_role(role_id(CHARLIE)), None);
+		assert_eq!(Account::role_id_of(CHARLIE), None);
+		assert_eq!(Account::claims_to_review().len(), 1);
 
-		// sysman(1) register vaccine type
-		assert_ok!(Account::register_vac_type(Origin::signed(ALICE)));
+		// 3 can claim again after rejection
+		assert_ok!(Account::claim_role(Origin::signed(CHARLIE), role_id(CHARLIE), Role::VAO));
+	});
+}
 
-		// NOT manufacture register vaccine type
+#[test]
+fn should_cause_error_for_claim_role() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		// system manager and user roles can not be claimed
+		assert_noop!(
+			Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::SYSMAN),
+			Error::<Test>::InvalidRole
+		);
 		assert_noop!(
-			Account::register_vac_info(Origin::signed(DAVE), Some(1)),
-			Error::<Test>::NotManufacture
+			Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::USER),
+			Error::<Test>::InvalidRole
 		);
 
-		// sysman register not exist vaccine type
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+
+		// claim again after claimed but before approved
+		assert_noop!(
+			Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM),
+			Error::<Test>::AlreadyClaimed
+		);
+		// claim another role for somebody else's role ID
 		assert_noop!(
-			Account::register_vac_info(Origin::signed(BOB), Some(2)),
-			Error::<Test>::NotRegisteredVaccineType
+			Account::claim_role(Origin::signed(CHARLIE), role_id(BOB), Role::VAD),
+			Error::<Test>::NotRoleOwner
+		);
+		// claim a second role ID with the same key
+		assert_noop!(
+			Account::claim_role(Origin::signed(BOB), role_id(CHARLIE), Role::VAD),
+			Error::<Test>::AccountAlreadyBound
+		);
+		// not system manager reviews the claim
+		assert_noop!(
+			Account::reject_role(Origin::signed(BOB), role_id(BOB), role_id(BOB), Role::VM),
+			Error::<Test>::PermissionDeny
+		);
+		// reject not claimed role ID
+		assert_noop!(
+			Account::reject_role(Origin::signed(ALICE), role_id(ALICE), role_id(DAVE), Role::VAD),
+			Error::<Test>::NotClaimed
 		);
 
-		//register vaciine info
-		assert_ok!(Account::register_vac_info(Origin::signed(BOB), Some(1)));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
 
-		// vaccine has been already registered
+		// claim again after approved
 		assert_noop!(
-			Account::register_vac_info(Origin::signed(BOB), Some(1)),
-			Error::<Test>::VaccineIsRegistered
+			Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM),
+			Error::<Test>::AlreadyApproved
 		);
+		// reject after approved
+		assert_noop!(
+			Account::reject_role(Origin::signed(ALICE), role_id(ALICE), role_id(BOB), Role::VM),
+			Error::<Test>::NotClaimed
+		);
+	});
+}
 
-		// approve vaccine
-		assert_ok!(Account::approve_vaccine(Origin::signed(CHARLIE), Some(1)));
+// ALICE(1): system manager
+// BOB(2): manufacture
+// DAVE(4): VAD
+
+#[test]
+fn should_revoke_suspend_and_reinstate_role() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_ok!(Account::claim_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD));
+
+		// pending roles can not be suspended or revoked
+		assert_noop!(
+			Account::revoke_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				Role::VAD,
+				1
+			),
+			Error::<Test>::NotApproved
+		);
+		assert_noop!(
+			Account::suspend_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				Role::VAD,
+				500,
+				1
+			),
+			Error::<Test>::NotApproved
+		);
 
-		// not VAO register vaciine info
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
+
+		// only system manager changes the status
 		assert_noop!(
-			Account::approve_vaccine(Origin::signed(DAVE), Some(1)),
-			Error::<Test>::NotOrganization
+			Account::revoke_role(Origin::signed(BOB), role_id(BOB), role_id(DAVE), Role::VAD, 1),
+			Error::<Test>::PermissionDeny
 		);
 
-		// VAO register not exist vaciine id
+		// suspend the manufacture for 500ms
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			500,
+			7
+		));
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Suspended { until: 1_500 })
+		);
 		assert_noop!(
-			Account::approve_vaccine(Origin::signed(CHARLIE), Some(2)),
-			Error::<Test>::NotRegisteredVaccine
+			Account::check_account(&role_id(BOB), Role::VM),
+			Error::<Test>::AccountSuspended
 		);
+		// the suspension lapses on its own
+		Timestamp::set_timestamp(1_500);
+		assert_ok!(Account::check_account(&role_id(BOB), Role::VM));
 
-		// TODO: duplicate vaccine approvement
-		// TODO: exceed max list size (3 in mock runtime)
+		// revoke the distributer
+		assert_ok!(Account::revoke_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			3
+		));
+		assert_noop!(
+			Account::check_union(&role_id(DAVE), Role::VM, Role::VAD),
+			Error::<Test>::AlreadyRevoked
+		);
+		assert_noop!(
+			Account::revoke_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				Role::VAD,
+				3
+			),
+			Error::<Test>::AlreadyRevoked
+		);
+		assert_noop!(
+			Account::suspend_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				Role::VAD,
+				500,
+				3
+			),
+			Error::<Test>::AlreadyRevoked
+		);
+		// a revoked role can not be approved through the claim review
+		assert_noop!(
+			Account::approve_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				Role::VAD,
+				None
+			),
+			Error::<Test>::InvalidStatus
+		);
+
+		// reinstate the distributer
+		assert_ok!(Account::reinstate_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			4
+		));
+		assert_ok!(Account::check_union(&role_id(DAVE), Role::VM, Role::VAD));
+		assert_noop!(
+			Account::reinstate_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				Role::VAD,
+				4
+			),
+			Error::<Test>::InvalidStatus
+		);
+
+		System::assert_last_event(Event::Account(crate::Event::Reinstated(
+			role_id(DAVE),
+			Role::VAD,
+			4,
+		)));
 	});
 }
 
 // ALICE(1): system manager
-// BOB(2): manufacture
-// CHARLIE(3): VAO
-// DAVE(4): VAD
-// EVE(5): VAD
-// FRANK(6): user
-// GEORGE(7):
+// BOB(2): manufacture and distributer
+
 #[test]
-fn should_cause_error_for_transfer_vaccine() {
+fn should_hold_multiple_roles() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(Account::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(Account::claim_vm(Origin::signed(BOB)));
-		// 2 is manufacture
-		assert_ok!(Account::approve_vm(Origin::signed(ALICE), BOB));
-		// 4 claimed to be distributer
-		assert_ok!(Account::claim_vad(Origin::signed(DAVE)));
-		// 4 is distributer
-		assert_ok!(Account::approve_vad(Origin::signed(ALICE), DAVE));
-		// 7 claimed to be distributer
-		assert_ok!(Account::claim_vad(Origin::signed(EVE)));
-		// 7 is distributer
-		assert_ok!(Account::approve_vad(Origin::signed(ALICE), EVE));
-		//register vaciine type info
-		assert_ok!(Account::register_vac_type(Origin::signed(ALICE)));
-		//register vaciine info
-		assert_ok!(Account::register_vac_info(Origin::signed(BOB), Some(1)));
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		// the manufacture runs its own distribution arm under the same identity
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VAD));
+		assert_eq!(Account::claims_to_review().len(), 2);
 
-		// Not manufacture or distributer transfer vaccine
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_ok!(Account::check_account(&role_id(BOB), Role::VM));
+		assert_noop!(Account::check_account(&role_id(BOB), Role::VAD), Error::<Test>::NotApproved);
+		assert_noop!(Account::check_account(&role_id(BOB), Role::VAO), Error::<Test>::InvalidRole);
+		assert_ok!(Account::check_union(&role_id(BOB), Role::VM, Role::VAD));
+
+		// rejecting one role keeps the identity and its other roles
+		assert_ok!(Account::reject_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VAD
+		));
+		assert_eq!(Account::account_role(role_id(BOB)), Some(BOB));
+		assert_noop!(Account::check_account(&role_id(BOB), Role::VAD), Error::<Test>::InvalidRole);
+
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VAD));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VAD,
+			None
+		));
+		// roles are suspended one at a time
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			1_000,
+			1
+		));
 		assert_noop!(
-			Account::transfer_vaccine(Origin::signed(FRANK), DAVE, Some(1)),
-			Error::<Test>::NotManufacture
+			Account::check_account(&role_id(BOB), Role::VM),
+			Error::<Test>::AccountSuspended
+		);
+		assert_ok!(Account::check_account(&role_id(BOB), Role::VAD));
+		assert_ok!(Account::check_union(&role_id(BOB), Role::VM, Role::VAD));
+	});
+}
+
+#[test]
+fn should_expire_time_bound_approval() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_ok!(Account::claim_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD));
+		// BOB is licensed for 5 seconds, DAVE without end
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			Some(5_000)
+		));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
+		assert_eq!(Account::role_expiry(role_id(BOB), Role::VM), Some(6_000));
+		assert_eq!(Account::role_expiry(role_id(DAVE), Role::VAD), None);
+		assert_ok!(Account::check_account(&role_id(BOB), Role::VM));
+
+		// the window is enforced before the sweep catches up
+		Timestamp::set_timestamp(6_000);
+		assert_noop!(Account::check_account(&role_id(BOB), Role::VM), Error::<Test>::RoleExpired);
+		Account::sweep_expired_roles();
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Approved)
 		);
 
-		// transfer to myself
+		// the sweep flips the role once its expiry period is over
+		Timestamp::set_timestamp(7_000);
+		Account::sweep_expired_roles();
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Expired)
+		);
+		assert_eq!(Account::role_expiry(role_id(BOB), Role::VM), None);
+		System::assert_last_event(Event::Account(crate::Event::RoleExpired(
+			role_id(BOB),
+			Role::VM,
+		)));
+		assert_noop!(Account::check_account(&role_id(BOB), Role::VM), Error::<Test>::RoleExpired);
+		assert_ok!(Account::check_account(&role_id(DAVE), Role::VAD));
+
+		// an expired role is renewed through a new claim
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			Some(5_000)
+		));
+		assert_ok!(Account::check_account(&role_id(BOB), Role::VM));
+		assert_eq!(Account::role_expiry(role_id(BOB), Role::VM), Some(12_000));
+	});
+}
+
+#[test]
+fn should_require_threshold_of_system_managers() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		// CHARLIE becomes a second system manager while one vote is enough
+		assert_ok!(Account::register_account(
+			Origin::root(),
+			role_id(CHARLIE),
+			CHARLIE,
+			Role::SYSMAN
+		));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			None
+		));
+		assert!(Account::only_system(role_id(CHARLIE)));
+
 		assert_noop!(
-			Account::transfer_vaccine(Origin::signed(BOB), BOB, Some(1)),
-			Error::<Test>::TransferByMyself
+			Account::set_proposal_threshold(Origin::signed(ALICE), 2),
+			DispatchError::BadOrigin
 		);
+		assert_noop!(
+			Account::set_proposal_threshold(Origin::root(), 0),
+			Error::<Test>::InvalidThreshold
+		);
+		assert_ok!(Account::set_proposal_threshold(Origin::root(), 2));
 
-		// Not owner transfer vaccine
+		// a single vote does not approve the role
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Pending)
+		);
+		assert_noop!(
+			Account::approve_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(BOB),
+				Role::VM,
+				None
+			),
+			Error::<Test>::AlreadyVoted
+		);
+		// a proposal that can not be carried out is refused
 		assert_noop!(
-			Account::transfer_vaccine(Origin::signed(DAVE), FRANK, Some(1)),
-			Error::<Test>::WrongVaccineOwner
+			Account::approve_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				Role::VAD,
+				None
+			),
+			Error::<Test>::NotFoundRole
 		);
 
-		// transfer not exist vaccine
+		// the second vote approves it
+		assert_ok!(Account::approve_role(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Approved)
+		);
+		System::assert_last_event(Event::Account(crate::Event::Approved(role_id(BOB), Role::VM)));
+
+		// adding a system manager needs both votes as well
+		assert_ok!(Account::register_account(Origin::root(), role_id(DAVE), DAVE, Role::SYSMAN));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::SYSMAN,
+			None
+		));
+		assert!(!Account::only_system(role_id(DAVE)));
+		assert_ok!(Account::approve_role(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(DAVE),
+			Role::SYSMAN,
+			None
+		));
+		assert!(Account::only_system(role_id(DAVE)));
+
+		// and so does adding one back
+		assert_ok!(Account::remove_system(Origin::signed(ALICE), role_id(ALICE), role_id(DAVE)));
+		assert_ok!(Account::remove_system(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(DAVE)
+		));
+		assert!(!Account::only_system(role_id(DAVE)));
+		assert_ok!(Account::add_system(Origin::signed(ALICE), role_id(ALICE), role_id(DAVE)));
+		assert!(!Account::only_system(role_id(DAVE)));
+		assert_ok!(Account::add_system(Origin::signed(CHARLIE), role_id(CHARLIE), role_id(DAVE)));
+		assert!(Account::only_system(role_id(DAVE)));
+	});
+}
+
+#[test]
+fn should_keep_the_window_of_a_role_revoked_when_it_ends() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			Some(5_000)
+		));
+		assert_ok!(Account::revoke_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			1
+		));
+
+		// the window ends while the role is revoked
+		Timestamp::set_timestamp(7_000);
+		Account::sweep_expired_roles();
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Revoked)
+		);
+		assert_eq!(Account::role_expiry(role_id(BOB), Role::VM), Some(6_000));
+
+		// lifting the revocation does not lift the end of the window
+		assert_ok!(Account::reinstate_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			2
+		));
+		assert_noop!(Account::check_account(&role_id(BOB), Role::VM), Error::<Test>::RoleExpired);
+	});
+}
+
+#[test]
+fn should_sweep_short_window_approved_after_long_one() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::claim_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD));
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		// DAVE is licensed for 100 seconds first, then BOB for 5 seconds
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			Some(100_000)
+		));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			Some(5_000)
+		));
+
+		// the sweep does not wait for the period of the longer window
+		Timestamp::set_timestamp(7_000);
+		Account::sweep_expired_roles();
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Expired)
+		);
+		assert_eq!(
+			Account::accounts(role_id(DAVE)).unwrap().roles.get(&Role::VAD),
+			Some(&RoleStatus::Approved)
+		);
+
+		// the sweep catches up 16 periods per block
+		Timestamp::set_timestamp(102_000);
+		for _ in 0..6 {
+			Account::sweep_expired_roles();
+		}
+		assert_eq!(
+			Account::accounts(role_id(DAVE)).unwrap().roles.get(&Role::VAD),
+			Some(&RoleStatus::Expired)
+		);
+	});
+}
+
+#[test]
+fn should_expire_proposals_after_deadline() {
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(
+			Origin::root(),
+			role_id(CHARLIE),
+			CHARLIE,
+			Role::SYSMAN
+		));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			None
+		));
+		assert_ok!(Account::set_proposal_threshold(Origin::root(), 2));
+
+		let action = pallet_account::ProposalAction::RemoveSystem(role_id(ALICE));
+		let hash = BlakeTwo256::hash_of(&action);
+		assert_ok!(Account::remove_system(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(ALICE)
+		));
+		assert_eq!(Account::proposals(hash).unwrap().deadline, 11_000);
+		assert_noop!(
+			Account::remove_expired_proposal(Origin::signed(BOB), hash),
+			Error::<Test>::ProposalNotExpired
+		);
+
+		// after the deadline the earlier vote no longer counts
+		Timestamp::set_timestamp(11_000);
+		assert_ok!(Account::remove_system(Origin::signed(ALICE), role_id(ALICE), role_id(ALICE)));
+		assert!(Account::only_system(role_id(ALICE)));
+		assert_eq!(Account::proposals(hash).unwrap().votes, vec![role_id(ALICE)]);
+
+		Timestamp::set_timestamp(21_000);
+		assert_ok!(Account::remove_expired_proposal(Origin::signed(BOB), hash));
+		assert_eq!(Account::proposals(hash), None);
+		System::assert_last_event(Event::Account(crate::Event::ProposalExpired(hash)));
+		assert_noop!(
+			Account::remove_expired_proposal(Origin::signed(BOB), hash),
+			Error::<Test>::NotFoundProposal
+		);
+	});
+}
+
+#[test]
+fn should_keep_system_managers_and_audit_log() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		System::set_block_number(3);
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(
+			Origin::root(),
+			role_id(CHARLIE),
+			CHARLIE,
+			Role::SYSMAN
+		));
+
+		// only approved system manager roles become system managers
+		assert_noop!(
+			Account::add_system(Origin::signed(ALICE), role_id(ALICE), role_id(DAVE)),
+			Error::<Test>::NotFoundRole
+		);
+		assert_noop!(
+			Account::add_system(Origin::signed(ALICE), role_id(ALICE), role_id(CHARLIE)),
+			Error::<Test>::NotApproved
+		);
+		// the last system manager stays
+		assert_noop!(
+			Account::remove_system(Origin::signed(ALICE), role_id(ALICE), role_id(ALICE)),
+			Error::<Test>::LastSystemManager
+		);
+		assert_noop!(
+			Account::revoke_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(ALICE),
+				Role::SYSMAN,
+				1
+			),
+			Error::<Test>::LastSystemManager
+		);
 		assert_noop!(
-			Account::transfer_vaccine(Origin::signed(BOB), DAVE, Some(3)),
-			Error::<Test>::NotRegisteredVaccine
+			Account::remove_system(Origin::signed(ALICE), role_id(ALICE), role_id(CHARLIE)),
+			Error::<Test>::NotSystemManager
 		);
 
-		// transfer vaccine
-		assert_ok!(Account::transfer_vaccine(Origin::signed(BOB), DAVE, Some(1)));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			None
+		));
+		assert_eq!(Account::admin_log_count(), 1);
+		assert_eq!(
+			Account::admin_log(0),
+			Some(AdminLogEntry {
+				who: vec![role_id(ALICE)],
+				change: AdminChange::AddSystem(role_id(CHARLIE)),
+				at: 1_000,
+				block: 3,
+			})
+		);
 
-		// receive not exist vaccine
+		// removing oneself needs a second manager even with a threshold of one
+		assert_ok!(Account::remove_system(Origin::signed(ALICE), role_id(ALICE), role_id(ALICE)));
+		assert!(Account::only_system(role_id(ALICE)));
+		assert_ok!(Account::remove_system(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(ALICE)
+		));
+		assert!(!Account::only_system(role_id(ALICE)));
+		assert_eq!(
+			Account::admin_log(1),
+			Some(AdminLogEntry {
+				who: vec![role_id(ALICE), role_id(CHARLIE)],
+				change: AdminChange::RemoveSystem(role_id(ALICE)),
+				at: 1_000,
+				block: 3,
+			})
+		);
+		// the approved SYSMAN role of a removed system manager grants nothing
 		assert_noop!(
-			Account::receive_vaccine(Origin::signed(DAVE), BOB, Some(3)),
-			Error::<Test>::NotRegisteredVaccine
+			Account::has_permission(&role_id(ALICE), pallet_account::Capability::RecallVaccine),
+			Error::<Test>::NotSystemManager
 		);
+		assert!(!Account::is_approved_actor(&ALICE));
+
+		assert_ok!(Account::set_proposal_threshold(Origin::root(), 2));
+		assert_eq!(Account::admin_log(2).unwrap().change, AdminChange::ThresholdSet(2));
+		assert_eq!(Account::admin_log_count(), 3);
+	});
+}
 
-		// not manufacture or distributer receive vaccine
+#[test]
+fn should_vote_on_system_manager_status_and_reinstatements() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		for who in [CHARLIE, DAVE] {
+			assert_ok!(Account::register_account(Origin::root(), role_id(who), who, Role::SYSMAN));
+			assert_ok!(Account::approve_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(who),
+				Role::SYSMAN,
+				None
+			));
+		}
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_ok!(Account::set_proposal_threshold(Origin::root(), 2));
+		let sysman_status =
+			|who| Account::accounts(role_id(who)).unwrap().roles[&Role::SYSMAN].clone();
+
+		// CHARLIE votes for BOB before being revoked
+		assert_ok!(Account::approve_role(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+
+		// a single system manager does not revoke another one
+		assert_ok!(Account::revoke_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			1
+		));
+		assert_eq!(sysman_status(CHARLIE), RoleStatus::Approved);
+		assert_ok!(Account::revoke_role(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			1
+		));
+		assert_eq!(sysman_status(CHARLIE), RoleStatus::Revoked);
+		System::assert_last_event(Event::Account(crate::Event::Revoked(
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			1,
+		)));
+
+		// a revoked system manager no longer votes, and its earlier votes no longer count
 		assert_noop!(
-			Account::receive_vaccine(Origin::signed(FRANK), BOB, Some(1)),
-			Error::<Test>::NotManufacture
+			Account::add_system(Origin::signed(CHARLIE), role_id(CHARLIE), role_id(BOB)),
+			Error::<Test>::AlreadyRevoked
+		);
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Pending)
+		);
+		assert_ok!(Account::approve_role(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_eq!(
+			Account::accounts(role_id(BOB)).unwrap().roles.get(&Role::VM),
+			Some(&RoleStatus::Approved)
 		);
 
-		// not specified receiver
+		// nor does a single system manager reinstate a role
+		assert_ok!(Account::reinstate_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			2
+		));
+		assert_eq!(sysman_status(CHARLIE), RoleStatus::Revoked);
+		assert_ok!(Account::reinstate_role(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			2
+		));
+		assert_eq!(sysman_status(CHARLIE), RoleStatus::Approved);
+
+		// or suspend a system manager
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::SYSMAN,
+			500,
+			3
+		));
+		assert_eq!(sysman_status(DAVE), RoleStatus::Approved);
+		assert_ok!(Account::suspend_role(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(DAVE),
+			Role::SYSMAN,
+			500,
+			3
+		));
+		assert_eq!(sysman_status(DAVE), RoleStatus::Suspended { until: 1_500 });
+	});
+}
+
+#[test]
+fn should_keep_an_active_system_manager() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		System::set_block_number(3);
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(
+			Origin::root(),
+			role_id(CHARLIE),
+			CHARLIE,
+			Role::SYSMAN
+		));
+		// a system manager role does not expire
 		assert_noop!(
-			Account::receive_vaccine(Origin::signed(EVE), FRANK, Some(1)),
-			Error::<Test>::NotVaccineBuyer
+			Account::approve_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(CHARLIE),
+				Role::SYSMAN,
+				Some(1_000)
+			),
+			Error::<Test>::ExpiringSystemManager
 		);
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			None
+		));
 
-		// receive from not vaccine owner
+		assert_ok!(Account::revoke_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			1
+		));
+		assert_eq!(
+			Account::admin_log(1),
+			Some(AdminLogEntry {
+				who: vec![role_id(ALICE)],
+				change: AdminChange::SystemRevoked(role_id(CHARLIE), 1),
+				at: 1_000,
+				block: 3,
+			})
+		);
+		// CHARLIE is still listed, but no longer counts as another system manager
+		assert!(Account::only_system(role_id(CHARLIE)));
 		assert_noop!(
-			Account::receive_vaccine(Origin::signed(DAVE), EVE, Some(1)),
-			Error::<Test>::WrongVaccineOwner
+			Account::revoke_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(ALICE),
+				Role::SYSMAN,
+				1
+			),
+			Error::<Test>::LastSystemManager
+		);
+		assert_noop!(
+			Account::remove_system(Origin::signed(ALICE), role_id(ALICE), role_id(ALICE)),
+			Error::<Test>::LastSystemManager
+		);
+		// nor is a revoked system manager role added back
+		assert_noop!(
+			Account::add_system(Origin::signed(ALICE), role_id(ALICE), role_id(CHARLIE)),
+			Error::<Test>::AlreadyRevoked
 		);
 
-		// receive vaccine
-		assert_ok!(Account::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)));
+		assert_ok!(Account::reinstate_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			2
+		));
+		assert_eq!(
+			Account::admin_log(2).unwrap().change,
+			AdminChange::SystemReinstated(role_id(CHARLIE), 2)
+		);
 
-		// receive twice
+		// a suspended system manager does not count either
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::SYSMAN,
+			500,
+			3
+		));
+		assert_eq!(
+			Account::admin_log(3).unwrap().change,
+			AdminChange::SystemSuspended(role_id(CHARLIE), 1_500, 3)
+		);
 		assert_noop!(
-			Account::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)),
-			Error::<Test>::VaccineAlreadyMine
+			Account::suspend_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(ALICE),
+				Role::SYSMAN,
+				500,
+				3
+			),
+			Error::<Test>::LastSystemManager
 		);
+
+		// until its suspension is over
+		Timestamp::set_timestamp(1_500);
+		assert!(Account::is_active_system(&role_id(CHARLIE)));
+		assert_eq!(Account::admin_log_count(), 4);
 	});
 }
 
-// ALICE(1): system manager
-// BOB(2): manufacture
-// CHARLIE(3): VAO
-// DAVE(4): VAD
-// EVE(5): VAD
-// FRANK(6): user
-// GEORGE(7): user
 #[test]
-fn should_cause_error_for_get_confirm_vaccine() {
+fn should_manage_sub_accounts() {
+	use pallet_account::{Capability, SubAccountKind};
+
+	const WAREHOUSE: u64 = 8;
+	const STAFF: u64 = 9;
+
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(Account::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(Account::claim_vm(Origin::signed(BOB)));
-		// 2 is manufacture
-		assert_ok!(Account::approve_vm(Origin::signed(ALICE), BOB));
-		// 4 claimed to be distributer
-		assert_ok!(Account::claim_vad(Origin::signed(DAVE)));
-		// 4 is distributer
-		assert_ok!(Account::approve_vad(Origin::signed(ALICE), DAVE));
-		// 7 claimed to be distributer
-		assert_ok!(Account::claim_vad(Origin::signed(EVE)));
-		// 7 is distributer
-		assert_ok!(Account::approve_vad(Origin::signed(ALICE), EVE));
-		//register vaciine type info
-		assert_ok!(Account::register_vac_type(Origin::signed(ALICE)));
-		//register vaciine info
-		assert_ok!(Account::register_vac_info(Origin::signed(BOB), Some(1)));
-		//register vaciine type info
-		assert_ok!(Account::register_vac_type(Origin::signed(ALICE)));
-		//register vaciine info
-		assert_ok!(Account::register_vac_info(Origin::signed(BOB), Some(2)));
-		// transfer vaccine
-		assert_ok!(Account::transfer_vaccine(Origin::signed(BOB), DAVE, Some(1)));
-		// receive vaccine
-		assert_ok!(Account::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)));
-		// transfer vaccine
-		assert_ok!(Account::transfer_vaccine(Origin::signed(BOB), DAVE, Some(2)));
-		// receive vaccine
-		assert_ok!(Account::receive_vaccine(Origin::signed(DAVE), BOB, Some(2)));
-		// transfer vaccine
-		assert_ok!(Account::transfer_vaccine(Origin::signed(DAVE), FRANK, Some(2)));
+		assert_ok!(Account::register_account(Origin::root(), role_id(CHARLIE), CHARLIE, Role::VAO));
+		assert_ok!(Account::register_account(Origin::root(), role_id(DAVE), DAVE, Role::VAD));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::VAO,
+			None
+		));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
 
-		// only manufacture or distributer
+		let warehouse = vec![Capability::TransferVaccine, Capability::ReceiveVaccine];
+		// only the organisation key creates sub-accounts
+		assert_noop!(
+			Account::create_sub_account(
+				Origin::signed(EVE),
+				role_id(DAVE),
+				role_id(WAREHOUSE),
+				WAREHOUSE,
+				SubAccountKind::Warehouse,
+				warehouse.clone()
+			),
+			Error::<Test>::NotRoleOwner
+		);
+		// only manufactures and distributers
+		assert_noop!(
+			Account::create_sub_account(
+				Origin::signed(CHARLIE),
+				role_id(CHARLIE),
+				role_id(WAREHOUSE),
+				WAREHOUSE,
+				SubAccountKind::Staff,
+				vec![Capability::ApproveVaccine]
+			),
+			Error::<Test>::InvalidRole
+		);
+		// a distributer does not pass on the manufacture capability
 		assert_noop!(
-			Account::transfer_get_vaccine_right(Origin::signed(FRANK), DAVE, Some(1)),
-			Error::<Test>::NotManufacture
+			Account::create_sub_account(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(WAREHOUSE),
+				WAREHOUSE,
+				SubAccountKind::Warehouse,
+				vec![Capability::RegisterVaccine]
+			),
+			Error::<Test>::InvalidRole
 		);
-		// only manufacture or distributer
 		assert_noop!(
-			Account::transfer_get_vaccine_right(Origin::signed(DAVE), FRANK, Some(3)),
-			Error::<Test>::NotRegisteredVaccine
+			Account::create_sub_account(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(CHARLIE),
+				WAREHOUSE,
+				SubAccountKind::Warehouse,
+				warehouse.clone()
+			),
+			Error::<Test>::AlreadyRegistered
 		);
-		// transfer to myself
 		assert_noop!(
-			Account::transfer_get_vaccine_right(Origin::signed(DAVE), DAVE, Some(1)),
-			Error::<Test>::TransferByMyself
+			Account::create_sub_account(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(WAREHOUSE),
+				CHARLIE,
+				SubAccountKind::Warehouse,
+				warehouse.clone()
+			),
+			Error::<Test>::AccountAlreadyBound
+		);
+
+		assert_ok!(Account::create_sub_account(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(WAREHOUSE),
+			WAREHOUSE,
+			SubAccountKind::Warehouse,
+			warehouse
+		));
+		assert_eq!(Account::account_role(role_id(WAREHOUSE)), Some(WAREHOUSE));
+		assert_eq!(Account::parent_of(&role_id(WAREHOUSE)), Some(role_id(DAVE)));
+		assert_eq!(Account::sub_accounts_of(&role_id(DAVE)), vec![role_id(WAREHOUSE)]);
+		assert_eq!(
+			Account::has_permission(&role_id(WAREHOUSE), Capability::ReceiveVaccine),
+			Ok(role_id(DAVE))
 		);
-		// not vaccine owner
 		assert_noop!(
-			Account::transfer_get_vaccine_right(Origin::signed(EVE), FRANK, Some(1)),
-			Error::<Test>::WrongVaccineOwner
+			Account::has_permission(&role_id(WAREHOUSE), Capability::AdministerVaccine),
+			Error::<Test>::PermissionDeny
+		);
+		assert_eq!(
+			Account::has_permission(&role_id(DAVE), Capability::AdministerVaccine),
+			Ok(role_id(DAVE))
 		);
-		// receive vaccine inoculation right
-		assert_ok!(Account::transfer_get_vaccine_right(Origin::signed(DAVE), FRANK, Some(1)));
 
-		// not send final transfer to me
+		// sub-accounts neither nest nor get registered as organisations
 		assert_noop!(
-			Account::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(3)),
-			Error::<Test>::NotRegisteredVaccine
+			Account::create_sub_account(
+				Origin::signed(WAREHOUSE),
+				role_id(WAREHOUSE),
+				role_id(STAFF),
+				STAFF,
+				SubAccountKind::Staff,
+				vec![]
+			),
+			Error::<Test>::PermissionDeny
 		);
-		// not vaccine user
 		assert_noop!(
-			Account::confirm_vaccine(Origin::signed(GEORGE), Some(DAVE), Some(1)),
-			Error::<Test>::NotVaccineBuyer
+			Account::register_account(Origin::root(), role_id(WAREHOUSE), STAFF, Role::VAD),
+			Error::<Test>::AlreadyRegistered
 		);
-		// not vaccine previous owner
+
+		// a suspended organisation suspends its sites
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			1_000,
+			1
+		));
 		assert_noop!(
-			Account::confirm_vaccine(Origin::signed(FRANK), Some(EVE), Some(1)),
-			Error::<Test>::WrongVaccineOwner
+			Account::has_permission(&role_id(WAREHOUSE), Capability::ReceiveVaccine),
+			Error::<Test>::AccountSuspended
 		);
-		// not transfer vaccine but transfer_get_vaccine_right
+
 		assert_noop!(
-			Account::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(2)),
-			Error::<Test>::NotSendFinalTransfer
+			Account::remove_sub_account(
+				Origin::signed(CHARLIE),
+				role_id(CHARLIE),
+				role_id(WAREHOUSE)
+			),
+			Error::<Test>::NotSubAccount
 		);
+		assert_ok!(Account::remove_sub_account(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(WAREHOUSE)
+		));
+		assert_eq!(Account::account_role(role_id(WAREHOUSE)), None);
+		assert_eq!(Account::sub_accounts(role_id(WAREHOUSE)), None);
+		assert!(Account::sub_accounts_of(&role_id(DAVE)).is_empty());
+	});
+}
+
+#[test]
+fn should_only_accept_uuid_role_ids() {
+	use codec::{Decode, Encode};
+	use pallet_account::UserId;
+
+	let valid = "3f2504e0-4f89-41d3-9a0c-0305e82c3301";
+	let role_id = UserId::try_from(valid).unwrap();
+	assert_eq!(role_id.as_bytes(), valid.as_bytes());
+	assert_eq!(UserId::decode(&mut &role_id.encode()[..]), Ok(role_id));
+	assert!(UserId::is_valid(UserId::default().as_bytes()));
 
-		// confirm use vaccine
-		assert_ok!(Account::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(1)));
+	for malformed in [
+		"0",
+		"3f2504e0-4f89-41d3-9a0c-0305e82c330",
+		"3f2504e0-4f89-41d3-9a0c-0305e82c33011",
+		"3F2504E0-4F89-41D3-9A0C-0305E82C3301",
+		"3f2504e0_4f89_41d3_9a0c_0305e82c3301",
+		"3f2504e0-4f89-41d3-9a0c-0305e82c330g",
+	] {
+		assert_eq!(UserId::try_from(malformed), Err(()));
+	}
+	// arbitrary bytes never decode into a role ID
+	assert!(UserId::decode(&mut &[b'x'; 36][..]).is_err());
+	assert!(UserId::decode(&mut &b"0"[..]).is_err());
+}
+
+#[test]
+fn should_review_organisation_profile() {
+	use sp_core::H256;
 
-		// not send final transfer to me
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
+		let licence = H256::repeat_byte(1);
+
+		assert_noop!(
+			Account::update_profile(
+				Origin::signed(CHARLIE),
+				role_id(BOB),
+				b"Bob Pharma".to_vec(),
+				b"LIC-1".to_vec(),
+				b"JP".to_vec(),
+				vec![licence]
+			),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_noop!(
+			Account::update_profile(
+				Origin::signed(BOB),
+				role_id(BOB),
+				b"Bob Pharmaceutical Company".to_vec(),
+				b"LIC-1".to_vec(),
+				b"JP".to_vec(),
+				vec![licence]
+			),
+			Error::<Test>::ProfileTooLong
+		);
+		assert_noop!(
+			Account::update_profile(
+				Origin::signed(BOB),
+				role_id(BOB),
+				b"Bob Pharma".to_vec(),
+				b"LIC-1".to_vec(),
+				b"JP".to_vec(),
+				vec![licence; 3]
+			),
+			Error::<Test>::TooManyDocuments
+		);
+
+		assert_ok!(Account::update_profile(
+			Origin::signed(BOB),
+			role_id(BOB),
+			b"Bob Pharma".to_vec(),
+			b"LIC-1".to_vec(),
+			b"JP".to_vec(),
+			vec![licence]
+		));
+		assert_eq!(Account::profiles(role_id(BOB)), None);
+		assert_noop!(
+			Account::review_profile(Origin::signed(BOB), role_id(BOB), role_id(BOB), true),
+			Error::<Test>::PermissionDeny
+		);
+
+		Timestamp::set_timestamp(2_000);
+		assert_ok!(Account::review_profile(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			true
+		));
+		let profile = Account::profiles(role_id(BOB)).unwrap();
+		assert_eq!(profile.licence_number.to_vec(), b"LIC-1".to_vec());
+		assert_eq!(profile.documents.to_vec(), vec![licence]);
+		assert_eq!(profile.submitted_at, 1_000);
+		assert_eq!(profile.reviewed_by, Some(role_id(ALICE)));
+		assert_eq!(profile.reviewed_at, Some(2_000));
+		assert_eq!(Account::pending_profiles(role_id(BOB)), None);
+
+		// an update stays pending and the reviewed profile remains in effect until approved
+		assert_ok!(Account::update_profile(
+			Origin::signed(BOB),
+			role_id(BOB),
+			b"Bob Pharma".to_vec(),
+			b"LIC-2".to_vec(),
+			b"JP".to_vec(),
+			vec![H256::repeat_byte(2)]
+		));
+		assert_ok!(Account::review_profile(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			false
+		));
+		System::assert_last_event(Event::Account(crate::Event::ProfileRejected(
+			role_id(BOB),
+			role_id(ALICE),
+		)));
+		assert_eq!(Account::profiles(role_id(BOB)), Some(profile));
+		assert_noop!(
+			Account::review_profile(Origin::signed(ALICE), role_id(ALICE), role_id(BOB), true),
+			Error::<Test>::NotFoundProfile
+		);
+	});
+}
+
+#[test]
+fn should_rotate_and_recover_keys() {
+	const NEW_KEY: u64 = 8;
+	const LOST_KEY_REPLACEMENT: u64 = 9;
+	const SYSTEM_KEY_REPLACEMENT: u64 = 10;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
+		assert_ok!(Account::register_account(Origin::root(), role_id(DAVE), DAVE, Role::VAD));
+		for who in [CHARLIE, EVE] {
+			assert_ok!(Account::register_account(Origin::root(), role_id(who), who, Role::SYSMAN));
+			assert_ok!(Account::approve_role(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(who),
+				Role::SYSMAN,
+				None
+			));
+		}
+
+		// rotation is signed by the current key
+		assert_noop!(
+			Account::rotate_key(Origin::signed(CHARLIE), role_id(BOB), NEW_KEY),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_noop!(
+			Account::rotate_key(Origin::signed(BOB), role_id(BOB), DAVE),
+			Error::<Test>::AccountAlreadyBound
+		);
+		assert_ok!(Account::rotate_key(Origin::signed(BOB), role_id(BOB), NEW_KEY));
+		assert_eq!(Account::account_role(role_id(BOB)), Some(NEW_KEY));
+		assert_eq!(Account::role_id_of(BOB), None);
+		assert_eq!(Account::role_id_of(NEW_KEY), Some(role_id(BOB)));
+		assert_noop!(Account::check_owner(&BOB, &role_id(BOB)), Error::<Test>::NotRoleOwner);
+		assert_ok!(Account::check_owner(&NEW_KEY, &role_id(BOB)));
+
+		// DAVE lost the key, a system manager starts the recovery
+		assert_noop!(
+			Account::initiate_recovery(
+				Origin::signed(BOB),
+				role_id(BOB),
+				role_id(DAVE),
+				LOST_KEY_REPLACEMENT
+			),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Account::initiate_recovery(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			LOST_KEY_REPLACEMENT
+		));
+		assert_eq!(Account::recoveries(role_id(DAVE)).unwrap().execute_after, 6_000);
+		assert_noop!(
+			Account::initiate_recovery(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				role_id(DAVE),
+				LOST_KEY_REPLACEMENT
+			),
+			Error::<Test>::RecoveryInProgress
+		);
+
+		// the current key may be the lost one, it only disputes the recovery
+		assert_ok!(Account::cancel_recovery(Origin::signed(DAVE), role_id(DAVE), role_id(DAVE)));
+		assert!(Account::recoveries(role_id(DAVE)).unwrap().disputed);
+		System::assert_last_event(Event::Account(crate::Event::RecoveryDisputed(role_id(DAVE))));
+		// and can not move the role ID away from it
+		assert_noop!(
+			Account::rotate_key(Origin::signed(DAVE), role_id(DAVE), NEW_KEY + 2),
+			Error::<Test>::RecoveryInProgress
+		);
+
+		// a system manager cancels the recovery
+		assert_ok!(Account::cancel_recovery(Origin::signed(ALICE), role_id(ALICE), role_id(DAVE)));
+		assert_eq!(Account::recoveries(role_id(DAVE)), None);
+		assert_noop!(
+			Account::complete_recovery(Origin::signed(LOST_KEY_REPLACEMENT), role_id(DAVE)),
+			Error::<Test>::NotFoundRecovery
+		);
+
+		assert_ok!(Account::initiate_recovery(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			LOST_KEY_REPLACEMENT
+		));
+		assert_noop!(
+			Account::complete_recovery(Origin::signed(LOST_KEY_REPLACEMENT), role_id(DAVE)),
+			Error::<Test>::RecoveryNotReady
+		);
+		assert_ok!(Account::cancel_recovery(Origin::signed(DAVE), role_id(DAVE), role_id(DAVE)));
+		Timestamp::set_timestamp(6_000);
+		assert_noop!(
+			Account::complete_recovery(Origin::signed(LOST_KEY_REPLACEMENT), role_id(DAVE)),
+			Error::<Test>::RecoveryDisputed
+		);
+
+		// or another system manager overrides the objection
+		assert_noop!(
+			Account::confirm_recovery(Origin::signed(DAVE), role_id(DAVE), role_id(DAVE)),
+			Error::<Test>::PermissionDeny
+		);
+		assert_noop!(
+			Account::confirm_recovery(Origin::signed(ALICE), role_id(ALICE), role_id(DAVE)),
+			Error::<Test>::RecoveryInitiator
+		);
+		assert_ok!(Account::confirm_recovery(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(DAVE)
+		));
+		System::assert_last_event(Event::Account(crate::Event::RecoveryConfirmed(
+			role_id(DAVE),
+			role_id(CHARLIE),
+		)));
+		assert_noop!(
+			Account::confirm_recovery(Origin::signed(EVE), role_id(EVE), role_id(DAVE)),
+			Error::<Test>::RecoveryNotDisputed
+		);
+		assert_noop!(
+			Account::complete_recovery(Origin::signed(EVE), role_id(DAVE)),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Account::complete_recovery(Origin::signed(LOST_KEY_REPLACEMENT), role_id(DAVE)));
+		assert_eq!(Account::account_role(role_id(DAVE)), Some(LOST_KEY_REPLACEMENT));
+		assert_eq!(Account::role_id_of(DAVE), None);
+		System::assert_last_event(Event::Account(crate::Event::KeyRecovered(
+			role_id(DAVE),
+			LOST_KEY_REPLACEMENT,
+		)));
+
+		// the key of a system manager is only recovered once the threshold is reached
+		assert_ok!(Account::set_proposal_threshold(Origin::root(), 2));
+		assert_ok!(Account::initiate_recovery(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			SYSTEM_KEY_REPLACEMENT
+		));
+		assert_eq!(Account::recoveries(role_id(CHARLIE)), None);
+		assert_ok!(Account::initiate_recovery(
+			Origin::signed(EVE),
+			role_id(EVE),
+			role_id(CHARLIE),
+			SYSTEM_KEY_REPLACEMENT
+		));
+		let recovery = Account::recoveries(role_id(CHARLIE)).unwrap();
+		assert_eq!(recovery.initiated_by, role_id(ALICE));
+		assert_eq!(recovery.new_account, SYSTEM_KEY_REPLACEMENT);
+		assert_ok!(Account::cancel_recovery(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(CHARLIE)
+		));
+		assert_noop!(
+			Account::confirm_recovery(Origin::signed(ALICE), role_id(ALICE), role_id(CHARLIE)),
+			Error::<Test>::RecoveryInitiator
+		);
+		assert_ok!(Account::confirm_recovery(Origin::signed(EVE), role_id(EVE), role_id(CHARLIE)));
+	});
+}
+
+#[test]
+fn should_edit_permission_table_through_governance() {
+	use pallet_account::Capability;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		assert_ok!(Account::register_account(Origin::root(), role_id(CHARLIE), CHARLIE, Role::VAO));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::VAO,
+			None
+		));
+		assert_eq!(Account::permissions(Role::VAO), None);
+		assert_eq!(Account::capabilities_of(&Role::VAO), vec![Capability::ApproveVaccine]);
+		assert_noop!(
+			Account::has_permission(&role_id(CHARLIE), Capability::ReceiveVaccine),
+			Error::<Test>::InvalidRole
+		);
+
+		assert_noop!(
+			Account::set_role_permissions(
+				Origin::signed(CHARLIE),
+				role_id(CHARLIE),
+				Role::VAO,
+				vec![Capability::ReceiveVaccine]
+			),
+			Error::<Test>::PermissionDeny
+		);
+		assert_ok!(Account::set_role_permissions(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			Role::VAO,
+			vec![
+				Capability::ReceiveVaccine,
+				Capability::ApproveVaccine,
+				Capability::ReceiveVaccine
+			]
+		));
+		assert_eq!(
+			Account::permissions(Role::VAO),
+			Some(vec![Capability::ReceiveVaccine, Capability::ApproveVaccine])
+		);
+		assert_eq!(
+			Account::has_permission(&role_id(CHARLIE), Capability::ReceiveVaccine),
+			Ok(role_id(CHARLIE))
+		);
+		assert_eq!(
+			Account::admin_log(0).unwrap().change,
+			AdminChange::PermissionsSet(
+				Role::VAO,
+				vec![Capability::ReceiveVaccine, Capability::ApproveVaccine]
+			)
+		);
+
+		// an empty table withdraws every capability of the role
+		assert_ok!(Account::set_role_permissions(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			Role::VAO,
+			vec![]
+		));
+		assert_noop!(
+			Account::has_permission(&role_id(CHARLIE), Capability::ApproveVaccine),
+			Error::<Test>::InvalidRole
+		);
+	});
+}
+
+#[test]
+fn should_delegate_capabilities_to_operator_keys() {
+	use pallet_account::{Capability, Delegation};
+	const TABLET: u64 = 8;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(Origin::root(), role_id(DAVE), DAVE, Role::VAD));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
+
+		// only the key of the principal delegates, and only what the principal may do
+		assert_noop!(
+			Account::delegate(
+				Origin::signed(TABLET),
+				role_id(DAVE),
+				TABLET,
+				vec![Capability::AdministerVaccine],
+				10_000
+			),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_noop!(
+			Account::delegate(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				TABLET,
+				vec![Capability::ApproveVaccine],
+				10_000
+			),
+			Error::<Test>::InvalidRole
+		);
+		assert_noop!(
+			Account::delegate(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				TABLET,
+				vec![Capability::AdministerVaccine],
+				20_001
+			),
+			Error::<Test>::InvalidDelegation
+		);
+		assert_noop!(
+			Account::delegate(Origin::signed(DAVE), role_id(DAVE), TABLET, vec![], 10_000),
+			Error::<Test>::InvalidDelegation
+		);
+
+		assert_ok!(Account::delegate(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			TABLET,
+			vec![Capability::AdministerVaccine],
+			10_000
+		));
+		assert_eq!(
+			Account::delegations(role_id(DAVE), TABLET),
+			Some(Delegation {
+				capabilities: vec![Capability::AdministerVaccine],
+				expires_at: 11_000
+			})
+		);
+		System::assert_last_event(Event::Account(crate::Event::Delegated(
+			role_id(DAVE),
+			TABLET,
+			vec![Capability::AdministerVaccine],
+			11_000,
+		)));
+
+		// the delegation is scoped and does not make the tablet the owner
+		assert_ok!(Account::check_delegate(&TABLET, &role_id(DAVE), Capability::AdministerVaccine));
+		assert_noop!(
+			Account::check_delegate(&TABLET, &role_id(DAVE), Capability::TransferVaccine),
+			Error::<Test>::PermissionDeny
+		);
+		assert_noop!(
+			Account::check_delegate(&EVE, &role_id(DAVE), Capability::AdministerVaccine),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_noop!(Account::check_owner(&TABLET, &role_id(DAVE)), Error::<Test>::NotRoleOwner);
+
+		Timestamp::set_timestamp(11_000);
+		assert_noop!(
+			Account::check_delegate(&TABLET, &role_id(DAVE), Capability::AdministerVaccine),
+			Error::<Test>::DelegationExpired
+		);
+
+		// withdrawn by the principal or the operator key itself
+		assert_noop!(
+			Account::revoke_delegation(Origin::signed(EVE), role_id(DAVE), TABLET),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Account::revoke_delegation(Origin::signed(TABLET), role_id(DAVE), TABLET));
+		assert_eq!(Account::delegations(role_id(DAVE), TABLET), None);
+		assert_noop!(
+			Account::revoke_delegation(Origin::signed(DAVE), role_id(DAVE), TABLET),
+			Error::<Test>::NotFoundDelegation
+		);
+	});
+}
+
+#[test]
+fn should_erase_user_accounts_on_request() {
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
+		assert_ok!(Account::register_account(Origin::root(), role_id(EVE), EVE, Role::USER));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(EVE),
+			Role::USER,
+			Some(10_000)
+		));
+
+		// only the user asks, and only for a plain user account
+		assert_noop!(
+			Account::request_erasure(Origin::signed(BOB), role_id(EVE)),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_noop!(
+			Account::request_erasure(Origin::signed(BOB), role_id(BOB)),
+			Error::<Test>::NotErasable
+		);
+		assert_noop!(
+			Account::confirm_erasure(Origin::signed(ALICE), role_id(ALICE), role_id(EVE)),
+			Error::<Test>::NotFoundErasure
+		);
+		// a reviewed profile and a pending update hold personal data as well
+		let update_profile = || {
+			Account::update_profile(
+				Origin::signed(EVE),
+				role_id(EVE),
+				b"Eve".to_vec(),
+				b"LIC-1".to_vec(),
+				b"JP".to_vec(),
+				vec![],
+			)
+		};
+		assert_ok!(update_profile());
+		assert_ok!(Account::review_profile(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(EVE),
+			true
+		));
+		assert_ok!(update_profile());
+		assert!(Account::profiles(role_id(EVE)).is_some());
+		assert!(Account::pending_profiles(role_id(EVE)).is_some());
+		assert_ok!(Account::request_erasure(Origin::signed(EVE), role_id(EVE)));
+		assert_eq!(Account::erasure_requests(role_id(EVE)), Some(1_000));
+		System::assert_last_event(Event::Account(crate::Event::ErasureRequested(role_id(EVE))));
+
+		// confirmed by a system manager
+		assert_noop!(
+			Account::confirm_erasure(Origin::signed(BOB), role_id(BOB), role_id(EVE)),
+			Error::<Test>::PermissionDeny
+		);
+		Timestamp::set_timestamp(2_000);
+		assert_ok!(Account::confirm_erasure(Origin::signed(ALICE), role_id(ALICE), role_id(EVE)));
+
+		assert_eq!(Account::accounts(role_id(EVE)), None);
+		assert_eq!(Account::account_role(role_id(EVE)), None);
+		assert_eq!(Account::role_id_of(EVE), None);
+		assert_eq!(Account::role_expiry(role_id(EVE), Role::USER), None);
+		assert_eq!(Account::erasure_requests(role_id(EVE)), None);
+		assert_eq!(Account::profiles(role_id(EVE)), None);
+		assert_eq!(Account::pending_profiles(role_id(EVE)), None);
+		let tombstone = BlakeTwo256::hash_of(&role_id(EVE));
+		assert_eq!(
+			Account::tombstones(tombstone),
+			Some(Tombstone { erased_at: 2_000, confirmed_by: role_id(ALICE) })
+		);
+		System::assert_last_event(Event::Account(crate::Event::AccountErased(
+			tombstone,
+			role_id(ALICE),
+		)));
+
+		// the role ID is retired, the key is free again
+		assert_noop!(
+			Account::register_account(Origin::root(), role_id(EVE), EVE, Role::USER),
+			Error::<Test>::AlreadyErased
+		);
+		assert_noop!(
+			Account::claim_role(Origin::signed(EVE), role_id(EVE), Role::VAD),
+			Error::<Test>::AlreadyErased
+		);
+		assert_ok!(Account::register_account(Origin::root(), role_id(6), EVE, Role::USER));
+
+		// the expiry sweep skips the erased approval
+		Timestamp::set_timestamp(20_000);
+		Account::sweep_expired_roles();
+		assert_eq!(Account::accounts(role_id(EVE)), None);
+	});
+}
+
+// DAVE(4) resigns with its warehouse 8 and operator key 9, EVE(5) claims the role ID next
+#[test]
+fn should_release_everything_under_a_resigned_role_id() {
+	use crate::{Capability, DidService, KeyType, SubAccountKind, VerificationKey};
+
+	const WAREHOUSE: u64 = 8;
+	const OPERATOR: u64 = 9;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::claim_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
+		assert_ok!(Account::create_sub_account(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(WAREHOUSE),
+			WAREHOUSE,
+			SubAccountKind::Warehouse,
+			vec![Capability::ReceiveVaccine]
+		));
+		assert_ok!(Account::delegate(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			OPERATOR,
+			vec![Capability::TransferVaccine],
+			10_000
+		));
+		assert_ok!(Account::update_profile(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			b"Dave Clinics".to_vec(),
+			b"LIC-4".to_vec(),
+			b"JP".to_vec(),
+			vec![]
+		));
+		assert_ok!(Account::review_profile(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			true
+		));
+		assert_ok!(Account::set_did_document(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			None,
+			vec![VerificationKey {
+				id: b"key-1".to_vec(),
+				key_type: KeyType::Ed25519,
+				public_key: vec![7; 32],
+			}],
+			Vec::<DidService>::new()
+		));
+
+		assert_ok!(Account::resign_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD));
+		assert_eq!(Account::accounts(role_id(DAVE)), None);
+		assert_eq!(Account::sub_accounts(role_id(WAREHOUSE)), None);
+		assert_eq!(Account::role_id_of(WAREHOUSE), None);
+
+		// the next holder of the role ID takes over nothing of DAVE
+		assert_ok!(Account::claim_role(Origin::signed(EVE), role_id(DAVE), Role::VAD));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
+		assert_ok!(Account::check_owner(&EVE, &role_id(DAVE)));
+		assert!(Account::has_permission(&role_id(WAREHOUSE), Capability::ReceiveVaccine).is_err());
+		assert!(Account::check_delegate(&OPERATOR, &role_id(DAVE), Capability::TransferVaccine)
+			.is_err());
+		assert_eq!(Account::sub_accounts_of(&role_id(DAVE)), vec![]);
+		assert_eq!(Account::profiles(role_id(DAVE)), None);
+		assert_eq!(Account::did_documents(role_id(DAVE)), None);
+	});
+}
+
+#[test]
+fn should_reserve_release_and_slash_role_bonds() {
+	use pallet_account::Bond;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		// the bond is reserved from the claiming key and returned on a rejection
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_eq!(Balances::reserved_balance(BOB), 1_000);
+		assert_eq!(Account::bonds(role_id(BOB), Role::VM), Some(Bond { who: BOB, amount: 1_000 }));
+		assert_ok!(Account::reject_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM
+		));
+		assert_eq!(Balances::reserved_balance(BOB), 0);
+		assert_eq!(Account::bonds(role_id(BOB), Role::VM), None);
+
+		// and on a voluntary exit
+		assert_ok!(Account::claim_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_noop!(
+			Account::resign_role(Origin::signed(CHARLIE), role_id(BOB), Role::VM),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Account::resign_role(Origin::signed(BOB), role_id(BOB), Role::VM));
+		assert_eq!(Balances::reserved_balance(BOB), 0);
+		assert_eq!(Account::accounts(role_id(BOB)), None);
+		System::assert_last_event(Event::Account(crate::Event::RoleResigned(
+			role_id(BOB),
+			Role::VM,
+		)));
+
+		// root registrations are bonded as well, plain users are not
+		assert_noop!(
+			Account::register_account(Origin::root(), role_id(8), 8, Role::VAD),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_ok!(Account::register_account(Origin::root(), role_id(8), 8, Role::USER));
+		assert_ok!(Account::register_account(Origin::root(), role_id(DAVE), DAVE, Role::VAD));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			None
+		));
+		assert_eq!(Balances::reserved_balance(DAVE), 1_000);
+
+		// a suspended organisation can not walk away with its bond
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			10_000,
+			1
+		));
+		assert_noop!(
+			Account::resign_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD),
+			Error::<Test>::AccountSuspended
+		);
+
+		// slashing burns the bond and revokes the role
+		let issuance = Balances::total_issuance();
+		assert_noop!(
+			Account::slash_bond(Origin::signed(DAVE), role_id(DAVE), role_id(DAVE), Role::VAD),
+			Error::<Test>::PermissionDeny
+		);
+		assert_ok!(Account::slash_bond(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD
+		));
+		assert_eq!(Balances::reserved_balance(DAVE), 0);
+		assert_eq!(Balances::total_issuance(), issuance - 1_000);
+		assert_eq!(
+			Account::accounts(role_id(DAVE)).unwrap().roles.get(&Role::VAD),
+			Some(&RoleStatus::Revoked)
+		);
+		assert_eq!(
+			Account::admin_log(0).unwrap().change,
+			AdminChange::BondSlashed(role_id(DAVE), Role::VAD)
+		);
+		System::assert_last_event(Event::Account(crate::Event::BondSlashed(
+			role_id(DAVE),
+			Role::VAD,
+			1_000,
+		)));
+		assert_noop!(
+			Account::slash_bond(Origin::signed(ALICE), role_id(ALICE), role_id(DAVE), Role::VAD),
+			Error::<Test>::NotFoundBond
+		);
+		// the revocation stays on record
+		assert_noop!(
+			Account::resign_role(Origin::signed(DAVE), role_id(DAVE), Role::VAD),
+			Error::<Test>::AlreadyRevoked
+		);
+
+		// a revoked organisation can not walk away with its bond either
+		assert_ok!(Account::claim_role(Origin::signed(CHARLIE), role_id(CHARLIE), Role::VAO));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::VAO,
+			None
+		));
+		assert_ok!(Account::revoke_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(CHARLIE),
+			Role::VAO,
+			1
+		));
+		assert_noop!(
+			Account::resign_role(Origin::signed(CHARLIE), role_id(CHARLIE), Role::VAO),
+			Error::<Test>::AlreadyRevoked
+		);
+		assert_eq!(Balances::reserved_balance(CHARLIE), 1_000);
+	});
+}
+
+#[test]
+fn should_limit_free_transactions_of_approved_actors() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		// only keys of active roles are exempt
+		assert!(!Account::can_send_free(&EVE));
+		assert_ok!(Account::register_account(Origin::root(), role_id(EVE), EVE, Role::USER));
+		assert!(!Account::can_send_free(&EVE));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(EVE),
+			Role::USER,
+			None
+		));
+		assert!(Account::can_send_free(&EVE));
+
+		// two per period of ten blocks in the mock
+		assert!(Account::use_free_transaction(&EVE));
+		assert!(Account::use_free_transaction(&EVE));
+		assert_eq!(Account::free_transactions_left(&EVE), 0);
+		assert!(!Account::use_free_transaction(&EVE));
+		assert_eq!(Account::free_transactions_used(EVE), Some((0, 2)));
+
+		System::set_block_number(10);
+		assert_eq!(Account::free_transactions_left(&EVE), 2);
+		assert!(Account::use_free_transaction(&EVE));
+		assert_eq!(Account::free_transactions_used(EVE), Some((1, 1)));
+
+		// a suspended user pays again
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(EVE),
+			Role::USER,
+			10_000,
+			1
+		));
+		assert!(!Account::use_free_transaction(&EVE));
+	});
+}
+
+#[test]
+fn should_publish_and_resolve_did_documents() {
+	use crate::{DidDocument, DidResolution, DidService, KeyType, VerificationKey};
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
+		assert_ok!(Account::register_account(Origin::root(), role_id(CHARLIE), CHARLIE, Role::VAO));
+		let key = |id: &str, key_type: KeyType, len: usize| VerificationKey {
+			id: id.as_bytes().to_vec(),
+			key_type,
+			public_key: vec![7; len],
+		};
+		let service = |id: &str, endpoint: &str| DidService {
+			id: id.as_bytes().to_vec(),
+			service_type: b"LinkedDomains".to_vec(),
+			endpoint: endpoint.as_bytes().to_vec(),
+		};
+
+		// without a document the DID lists the bound key only
+		assert_eq!(
+			Account::resolve_did(&role_id(BOB)),
+			Some(DidResolution { account: BOB, document: None })
+		);
+		assert_eq!(Account::resolve_did(&role_id(DAVE)), None);
+
+		// only the owner publishes the document of a registered role ID
 		assert_noop!(
-			Account::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(1)),
-			Error::<Test>::VaccineAlreadyMine
+			Account::set_did_document(Origin::signed(CHARLIE), role_id(BOB), None, vec![], vec![]),
+			Error::<Test>::NotRoleOwner
 		);
+		assert_noop!(
+			Account::set_did_document(Origin::signed(DAVE), role_id(DAVE), None, vec![], vec![]),
+			Error::<Test>::NotFoundRole
+		);
+		assert_noop!(
+			Account::set_did_document(
+				Origin::signed(BOB),
+				role_id(BOB),
+				Some(role_id(DAVE)),
+				vec![],
+				vec![]
+			),
+			Error::<Test>::NotFoundRole
+		);
+
+		// entries are checked against the limits and their schemes
+		assert_noop!(
+			Account::set_did_document(
+				Origin::signed(BOB),
+				role_id(BOB),
+				None,
+				vec![
+					key("key-1", KeyType::Sr25519, 32),
+					key("key-2", KeyType::Ed25519, 32),
+					key("key-3", KeyType::Ecdsa, 33)
+				],
+				vec![]
+			),
+			Error::<Test>::TooManyDidEntries
+		);
+		assert_noop!(
+			Account::set_did_document(
+				Origin::signed(BOB),
+				role_id(BOB),
+				None,
+				vec![],
+				vec![service("certs", "https://certificates.example.org/vm")]
+			),
+			Error::<Test>::DidFieldTooLong
+		);
+		for keys in [
+			vec![key("key-1", KeyType::Sr25519, 33)],
+			vec![key("", KeyType::Sr25519, 32)],
+			vec![key("account", KeyType::Sr25519, 32)],
+			vec![key("key-1", KeyType::Sr25519, 32), key("key-1", KeyType::Ed25519, 32)],
+		] {
+			assert_noop!(
+				Account::set_did_document(Origin::signed(BOB), role_id(BOB), None, keys, vec![]),
+				Error::<Test>::InvalidDidEntry
+			);
+		}
+		assert_noop!(
+			Account::set_did_document(
+				Origin::signed(BOB),
+				role_id(BOB),
+				None,
+				vec![key("key-1", KeyType::Sr25519, 32)],
+				vec![service("key-1", "https://vm.example")]
+			),
+			Error::<Test>::InvalidDidEntry
+		);
+
+		let keys = vec![key("key-1", KeyType::Sr25519, 32), key("key-2", KeyType::Ecdsa, 33)];
+		assert_ok!(Account::set_did_document(
+			Origin::signed(BOB),
+			role_id(BOB),
+			Some(role_id(CHARLIE)),
+			keys.clone(),
+			vec![service("certs", "https://vm.example")]
+		));
+		System::assert_last_event(Event::Account(crate::Event::DidDocumentSet(role_id(BOB))));
+		let document = DidDocument {
+			controller: Some(role_id(CHARLIE)),
+			keys: keys.clone(),
+			services: vec![service("certs", "https://vm.example")],
+			updated_at: 1_000,
+		};
+		assert_eq!(Account::did_documents(role_id(BOB)), Some(document.clone()));
+		assert_eq!(
+			Account::resolve_did(&role_id(BOB)),
+			Some(DidResolution { account: BOB, document: Some(document) })
+		);
+
+		// the controller updates the document as well, strangers do not
+		assert_noop!(
+			Account::set_did_document(Origin::signed(EVE), role_id(BOB), None, vec![], vec![]),
+			Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Account::set_did_document(
+			Origin::signed(CHARLIE),
+			role_id(BOB),
+			Some(role_id(CHARLIE)),
+			keys,
+			vec![]
+		));
+		assert!(Account::did_documents(role_id(BOB)).unwrap().services.is_empty());
+
+		assert_ok!(Account::remove_did_document(Origin::signed(BOB), role_id(BOB)));
+		System::assert_last_event(Event::Account(crate::Event::DidDocumentRemoved(role_id(BOB))));
+		assert_eq!(Account::did_documents(role_id(BOB)), None);
+		assert_noop!(
+			Account::remove_did_document(Origin::signed(BOB), role_id(BOB)),
+			Error::<Test>::NotFoundDidDocument
+		);
+
+		// the document goes with an erased account
+		assert_ok!(Account::register_account(Origin::root(), role_id(EVE), EVE, Role::USER));
+		assert_ok!(Account::set_did_document(
+			Origin::signed(EVE),
+			role_id(EVE),
+			None,
+			vec![key("key-1", KeyType::Ed25519, 32)],
+			vec![]
+		));
+		assert_ok!(Account::request_erasure(Origin::signed(EVE), role_id(EVE)));
+		assert_ok!(Account::confirm_erasure(Origin::signed(ALICE), role_id(ALICE), role_id(EVE)));
+		assert_eq!(Account::did_documents(role_id(EVE)), None);
+		assert_eq!(Account::resolve_did(&role_id(EVE)), None);
+	});
+}
+
+#[test]
+fn mock_accounts_answer_like_the_pallet() {
+	use crate::{testing::MockAccounts, Capability, SubAccountKind};
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		// BOB: approved manufacture with a warehouse and an operator key, CHARLIE: pending
+		assert_ok!(Account::register_account(Origin::root(), role_id(BOB), BOB, Role::VM));
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			Role::VM,
+			None
+		));
+		assert_ok!(Account::register_account(Origin::root(), role_id(CHARLIE), CHARLIE, Role::VAO));
+		assert_ok!(Account::create_sub_account(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(EVE),
+			EVE,
+			SubAccountKind::Warehouse,
+			vec![Capability::ReceiveVaccine]
+		));
+		assert_ok!(Account::update_profile(
+			Origin::signed(BOB),
+			role_id(BOB),
+			b"Vaccines Ltd".to_vec(),
+			b"L-1".to_vec(),
+			b"JP".to_vec(),
+			vec![]
+		));
+		assert_ok!(Account::review_profile(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(BOB),
+			true
+		));
+		assert_ok!(Account::delegate(
+			Origin::signed(BOB),
+			role_id(BOB),
+			DAVE,
+			vec![Capability::TransferVaccine],
+			10_000
+		));
+
+		MockAccounts::set_role(&role_id(ALICE), ALICE, Role::SYSMAN, RoleStatus::Approved);
+		MockAccounts::set_role(&role_id(BOB), BOB, Role::VM, RoleStatus::Approved);
+		MockAccounts::set_role(&role_id(CHARLIE), CHARLIE, Role::VAO, RoleStatus::Pending);
+		MockAccounts::add_sub_account(
+			&role_id(BOB),
+			&role_id(EVE),
+			EVE,
+			vec![Capability::ReceiveVaccine],
+		);
+		MockAccounts::set_jurisdiction(&role_id(BOB), b"JP");
+		MockAccounts::delegate(&role_id(BOB), DAVE, vec![Capability::TransferVaccine]);
+
+		let roles = [Role::SYSMAN, Role::VM, Role::VAO, Role::VAD, Role::USER];
+		let capabilities = [
+			Capability::RegisterVaccine,
+			Capability::TransferVaccine,
+			Capability::ReceiveVaccine,
+			Capability::ApproveVaccine,
+			Capability::AdministerVaccine,
+			Capability::RegisterVaccineType,
+			Capability::RecallVaccine,
+		];
+		for who in [ALICE, BOB, CHARLIE, DAVE, EVE].map(role_id) {
+			assert_eq!(Account::roles_of(&who), MockAccounts::roles_of(&who));
+			assert_eq!(Account::parent_of(&who), MockAccounts::parent_of(&who));
+			assert_eq!(Account::organisation_of(&who), MockAccounts::organisation_of(&who));
+			assert_eq!(Account::jurisdiction_of(&who), MockAccounts::jurisdiction_of(&who));
+			assert_eq!(Account::account_of(&who), MockAccounts::account_of(&who));
+			for role in roles.clone() {
+				assert_eq!(
+					Account::role_status(&who, role.clone()),
+					MockAccounts::role_status(&who, role.clone())
+				);
+				assert_eq!(
+					Account::check_account(&who, role.clone()).is_ok(),
+					MockAccounts::check_account(&who, role.clone()).is_ok()
+				);
+				assert_eq!(
+					Account::check_claim_account(&who, role.clone()).is_ok(),
+					MockAccounts::check_claim_account(&who, role).is_ok()
+				);
+			}
+			for capability in capabilities {
+				assert_eq!(
+					Account::has_permission(&who, capability).ok(),
+					MockAccounts::has_permission(&who, capability).ok()
+				);
+			}
+			for key in [ALICE, BOB, CHARLIE, DAVE, EVE] {
+				assert_eq!(
+					Account::check_owner(&key, &who).is_ok(),
+					MockAccounts::check_owner(&key, &who).is_ok()
+				);
+				for capability in capabilities {
+					assert_eq!(
+						Account::check_delegate(&key, &who, capability).is_ok(),
+						MockAccounts::check_delegate(&key, &who, capability).is_ok()
+					);
+				}
+			}
+		}
+		assert_eq!(MockAccounts::jurisdiction_of(&role_id(EVE)), Some(b"JP".to_vec()));
+	});
+}
+
+#[test]
+fn should_migrate_legacy_role_ids() {
+	use crate::migrations::v1::{role_id_of, OldAccount};
+	use codec::Encode;
+	use frame_support::{
+		migration::{have_storage_value, put_storage_value},
+		traits::StorageVersion,
+		Blake2_128Concat, StorageHasher, Twox64Concat,
+	};
+
+	ExtBuilder::default().execute_with(|| {
+		// the baseline layout keyed by legacy identifiers of any form
+		let legacy_uuid = b"8B2C1E4A-7F3D-4C5B-9A1E-2D6F8C0B3A7E".to_vec();
+		let legacy_name = b"dave".to_vec();
+		let put_account = |old_id: &Vec<u8>, role, status| {
+			let key = Blake2_128Concat::hash(&old_id.encode());
+			put_storage_value(b"Account", b"Accounts", &key, OldAccount { role, status });
+		};
+		put_account(&legacy_uuid, Role::SYSMAN, RoleStatus::Approved);
+		put_account(&legacy_name, Role::VAD, RoleStatus::Pending);
+		let key = Twox64Concat::hash(&legacy_uuid.encode());
+		put_storage_value(b"Account", b"SystemManager", &key, true);
+		StorageVersion::new(0).put::<Account>();
+
+		pallet_account::migrations::v1::migrate::<Test>();
+
+		assert_eq!(StorageVersion::get::<Account>(), StorageVersion::new(1));
+		// UUIDs keep their value, other identifiers map to a UUID of their hash
+		let sysman = role_id_of(&legacy_uuid);
+		assert_eq!(sysman.as_bytes(), &b"8b2c1e4a-7f3d-4c5b-9a1e-2d6f8c0b3a7e"[..]);
+		let dave = role_id_of(&legacy_name);
+		assert!(crate::UserId::is_valid(dave.as_bytes()));
+		assert_eq!(dave, role_id_of(b"dave"));
+		assert_ne!(dave, role_id_of(b"eve"));
+
+		assert_eq!(
+			Account::accounts(&sysman),
+			Some(pallet_account::Account::new(Role::SYSMAN, RoleStatus::Approved))
+		);
+		assert!(Account::only_system(sysman));
+		assert_eq!(
+			Account::accounts(&dave),
+			Some(pallet_account::Account::new(Role::VAD, RoleStatus::Pending))
+		);
+		assert_eq!(
+			Account::pending_claims(&dave, Role::VAD),
+			Some(Claim { role: Role::VAD, claimed_at: 0 })
+		);
+		// nothing is left under the legacy keys
+		let key = Blake2_128Concat::hash(&legacy_name.encode());
+		assert!(!have_storage_value(b"Account", b"Accounts", &key));
+
+		// no key was bound before, root binds them
+		assert_eq!(Account::account_role(&sysman), None);
+		assert_noop!(
+			Account::bind_key(Origin::signed(ALICE), sysman.clone(), ALICE),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Account::bind_key(Origin::root(), role_id(EVE), EVE),
+			Error::<Test>::NotFoundRole
+		);
+		assert_ok!(Account::bind_key(Origin::root(), sysman.clone(), ALICE));
+		assert_noop!(
+			Account::bind_key(Origin::root(), sysman.clone(), BOB),
+			Error::<Test>::AlreadyRegistered
+		);
+		assert_noop!(
+			Account::bind_key(Origin::root(), dave.clone(), ALICE),
+			Error::<Test>::AccountAlreadyBound
+		);
+		assert_ok!(Account::bind_key(Origin::root(), dave.clone(), DAVE));
+		assert_eq!(Account::role_id_of(DAVE), Some(dave.clone()));
+
+		// and the migrated system manager reviews the migrated claim
+		assert_ok!(Account::approve_role(
+			Origin::signed(ALICE),
+			sysman,
+			dave.clone(),
+			Role::VAD,
+			None
+		));
+		assert_ok!(Account::check_account(&dave, Role::VAD));
 	});
 }
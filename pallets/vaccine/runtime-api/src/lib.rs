@@ -0,0 +1,23 @@
+//! Runtime API of the vaccine pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_account::RoleId;
+use pallet_vaccine::{QuotaUsage, VacId};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait VaccineQuotaApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// Vaccine calls made for `role_id` in the current window and its quota.
+		fn quota_usage(role_id: RoleId) -> QuotaUsage<BlockNumber>;
+	}
+
+	pub trait VaccineExpiryApi {
+		/// Vials owned by `holder` which expire within `within` milliseconds from now, expired
+		/// ones included, with their expiry and the soonest first.
+		fn expiring_vials(holder: RoleId, within: u64) -> Vec<(VacId, u64)>;
+	}
+}
@@ -0,0 +1,311 @@
+//! Property test feeding random call sequences of the account and vaccine pallets into the mock
+//! runtime. Every call may fail, but none may panic and the system manager set never empties.
+
+use crate::{mock::*, VacId, VacType};
+use frame_support::traits::Hooks;
+use pallet_account::{Capability, Role, RoleId, SubAccountKind};
+use sp_core::H256;
+use sp_runtime::traits::Dispatchable;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const SEEDS: u64 = 64;
+const STEPS: usize = 200;
+
+// signers 1..=7 are set up below, 8 is a stranger
+const SIGNERS: u64 = 8;
+
+// xorshift64*, good enough to drive the call sequences and reproducible from the seed
+struct Rng(u64);
+
+impl Rng {
+	fn new(seed: u64) -> Self {
+		Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		self.0 ^= self.0 >> 12;
+		self.0 ^= self.0 << 25;
+		self.0 ^= self.0 >> 27;
+		self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+	}
+
+	fn below(&mut self, n: u64) -> u64 {
+		self.next_u64() % n
+	}
+
+	fn chance(&mut self, percent: u64) -> bool {
+		self.below(100) < percent
+	}
+
+	fn signer(&mut self) -> u64 {
+		self.below(SIGNERS) + 1
+	}
+
+	// mostly the role ID bound to `who`, sometimes somebody else's or an unknown one
+	fn role_id_for(&mut self, who: u64) -> RoleId {
+		if self.chance(80) {
+			role_id(who)
+		} else {
+			role_id(self.below(SIGNERS + 2) + 1)
+		}
+	}
+
+	fn any_role_id(&mut self) -> RoleId {
+		role_id(self.below(SIGNERS + 2) + 1)
+	}
+
+	fn role(&mut self) -> Role {
+		match self.below(5) {
+			0 => Role::SYSMAN,
+			1 => Role::VM,
+			2 => Role::VAO,
+			3 => Role::VAD,
+			_ => Role::USER,
+		}
+	}
+
+	fn vac_type(&mut self) -> VacType {
+		match self.below(4) {
+			0 => VacType::COVID19,
+			1 => VacType::FLU,
+			2 => VacType::HPV,
+			_ => VacType::RUBELLA,
+		}
+	}
+
+	fn vac_id(&mut self) -> VacId {
+		format!("vac-{}", self.below(4)).into_bytes()
+	}
+
+	fn capabilities(&mut self) -> Vec<Capability> {
+		let all = [
+			Capability::RegisterVaccineType,
+			Capability::RegisterVaccine,
+			Capability::TransferVaccine,
+			Capability::ReceiveVaccine,
+			Capability::ApproveVaccine,
+			Capability::AdministerVaccine,
+			Capability::RecallVaccine,
+		];
+		all.iter().copied().filter(|_| self.chance(40)).collect()
+	}
+
+	fn proposal(&mut self) -> H256 {
+		let open: Vec<H256> = pallet_account::Proposals::<Test>::iter_keys().collect();
+		if !open.is_empty() && self.chance(80) {
+			open[self.below(open.len() as u64) as usize]
+		} else {
+			H256::from_low_u64_be(self.next_u64())
+		}
+	}
+
+	fn call(&mut self) -> (Origin, Call) {
+		let who = self.signer();
+		let me = self.role_id_for(who);
+		let call = match self.below(33) {
+			0 => Call::Account(pallet_account::Call::approve_role {
+				system: me,
+				target: self.any_role_id(),
+				role: self.role(),
+				valid_for: if self.chance(50) { Some(self.below(20_000)) } else { None },
+			}),
+			1 => Call::Account(pallet_account::Call::reject_role {
+				system: me,
+				target: self.any_role_id(),
+				role: self.role(),
+			}),
+			2 => Call::Account(pallet_account::Call::revoke_role {
+				system: me,
+				target: self.any_role_id(),
+				role: self.role(),
+				reason: self.below(4) as u32,
+			}),
+			3 => Call::Account(pallet_account::Call::suspend_role {
+				system: me,
+				target: self.any_role_id(),
+				role: self.role(),
+				duration: self.next_u64(),
+				reason: self.below(4) as u32,
+			}),
+			4 => Call::Account(pallet_account::Call::reinstate_role {
+				system: me,
+				target: self.any_role_id(),
+				role: self.role(),
+				reason: self.below(4) as u32,
+			}),
+			5 => Call::Account(pallet_account::Call::claim_role { role_id: me, role: self.role() }),
+			6 => {
+				let call = Call::Account(pallet_account::Call::register_account {
+					role_id: self.any_role_id(),
+					account_id: self.signer(),
+					role: self.role(),
+				});
+				return (Origin::root(), call)
+			},
+			7 => Call::Account(pallet_account::Call::add_system {
+				system: me,
+				user: self.any_role_id(),
+			}),
+			8 => Call::Account(pallet_account::Call::remove_system {
+				system: me,
+				user: self.any_role_id(),
+			}),
+			9 => {
+				let call = Call::Account(pallet_account::Call::set_proposal_threshold {
+					threshold: self.below(4) as u32,
+				});
+				return (Origin::root(), call)
+			},
+			10 => Call::Account(pallet_account::Call::remove_expired_proposal {
+				proposal: self.proposal(),
+			}),
+			11 => Call::Vaccine(crate::Call::register_vac_type {
+				sysman: me,
+				vac_type: self.vac_type(),
+				doses_per_vial: self.below(11) as u32,
+			}),
+			12 => Call::Vaccine(crate::Call::register_vac_info {
+				manufacture: me,
+				vac_id: self.vac_id(),
+				vac_type: self.vac_type(),
+				expires_at: self.below(60_000),
+			}),
+			13 => Call::Vaccine(crate::Call::transfer_vaccine {
+				sender: me,
+				buyer_id: self.any_role_id(),
+				vac_id: self.vac_id(),
+			}),
+			14 => Call::Vaccine(crate::Call::receive_vaccine {
+				receiver: me,
+				sender: self.any_role_id(),
+				vac_id: self.vac_id(),
+			}),
+			15 => Call::Vaccine(crate::Call::approve_vaccine {
+				organization: me,
+				vac_id: self.vac_id(),
+			}),
+			16 => Call::Vaccine(crate::Call::transfer_get_vaccine_right {
+				sender: me,
+				user_id: self.any_role_id(),
+				vac_id: self.vac_id(),
+			}),
+			17 => Call::Vaccine(crate::Call::confirm_vaccine {
+				user: me,
+				vac_owner: self.any_role_id(),
+				vac_id: self.vac_id(),
+			}),
+			18 => Call::Account(pallet_account::Call::create_sub_account {
+				parent: me,
+				sub: self.any_role_id(),
+				account_id: self.signer(),
+				kind: SubAccountKind::Warehouse,
+				capabilities: self.capabilities(),
+			}),
+			19 => Call::Account(pallet_account::Call::remove_sub_account {
+				parent: me,
+				sub: self.any_role_id(),
+			}),
+			20 => Call::Account(pallet_account::Call::delegate {
+				principal: me,
+				delegate: self.signer(),
+				capabilities: self.capabilities(),
+				duration: self.below(30_000),
+			}),
+			21 => Call::Account(pallet_account::Call::revoke_delegation {
+				principal: self.any_role_id(),
+				delegate: self.signer(),
+			}),
+			22 => Call::Account(pallet_account::Call::request_erasure { role_id: me }),
+			23 => Call::Account(pallet_account::Call::confirm_erasure {
+				system: me,
+				target: self.any_role_id(),
+			}),
+			24 =>
+				Call::Account(pallet_account::Call::resign_role { role_id: me, role: self.role() }),
+			25 => Call::Account(pallet_account::Call::slash_bond {
+				system: me,
+				target: self.any_role_id(),
+				role: self.role(),
+			}),
+			27 =>
+				Call::Vaccine(crate::Call::quarantine_vaccine { holder: me, vac_id: self.vac_id() }),
+			28 => Call::Vaccine(crate::Call::release_vaccine { holder: me, vac_id: self.vac_id() }),
+			29 => Call::Vaccine(crate::Call::destroy_vaccine { holder: me, vac_id: self.vac_id() }),
+			30 => Call::Vaccine(crate::Call::expire_vaccine { vac_id: self.vac_id() }),
+			31 => Call::Vaccine(crate::Call::recall {
+				recaller: me,
+				target: crate::RecallTarget::Vial(self.vac_id()),
+			}),
+			32 => Call::Vaccine(crate::Call::recall {
+				recaller: me,
+				target: crate::RecallTarget::Lot(b"vac".to_vec()),
+			}),
+			// vials of the lot `vac` share their IDs with the single vials
+			_ => Call::Vaccine(crate::Call::register_lot {
+				manufacture: me,
+				lot_number: b"vac".to_vec(),
+				manufactured_at: 1_000,
+				quantity: self.below(12) as u32,
+				vac_type: self.vac_type(),
+				expires_at: self.below(60_000),
+			}),
+		};
+		(Origin::signed(who), call)
+	}
+}
+
+// ALICE(1): system manager
+// 2: manufacture, 3: VAO, 4 and 5: VAD, 6 and 7: user
+fn setup() {
+	let roles = vec![
+		(2, Role::VM),
+		(3, Role::VAO),
+		(4, Role::VAD),
+		(5, Role::VAD),
+		(6, Role::USER),
+		(7, Role::USER),
+	];
+	for (who, role) in roles {
+		let _ = Account::register_account(Origin::root(), role_id(who), who, role.clone());
+		let _ = Account::approve_role(Origin::signed(1), role_id(1), role_id(who), role, None);
+	}
+	let _ = Vaccine::register_vac_type(Origin::signed(1), role_id(1), VacType::COVID19, 8);
+}
+
+fn run(seed: u64) {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		let mut rng = Rng::new(seed);
+		let mut now = 1_000;
+		Timestamp::set_timestamp(now);
+		setup();
+
+		for step in 0..STEPS {
+			if rng.chance(20) {
+				let block = System::block_number() + 1;
+				now += rng.below(5_000);
+				System::set_block_number(block);
+				Timestamp::set_timestamp(now);
+				Account::on_initialize(block);
+			}
+
+			let (origin, call) = rng.call();
+			let _ = call.dispatch(origin);
+
+			assert!(
+				pallet_account::SystemManager::<Test>::iter_keys()
+					.any(|system| Account::is_active_system(&system)),
+				"seed {} step {}: no active system manager is left",
+				seed,
+				step
+			);
+		}
+	});
+}
+
+#[test]
+fn random_call_sequences_never_panic() {
+	for seed in 0..SEEDS {
+		let result = catch_unwind(AssertUnwindSafe(|| run(seed)));
+		assert!(result.is_ok(), "call sequence of seed {} panicked", seed);
+	}
+}
@@ -6,7 +6,7 @@ use frame_system::pallet_prelude::*;
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// <https://docs.substrate.io/v3/runtime/frame>
 pub use pallet::*;
-use pallet_account::{AccountPallet, Role, RoleId};
+use pallet_account::{AccountPallet, Capability, OnErasure, OnSubAccountRemoval, Role, RoleId};
 use scale_info::TypeInfo;
 use sp_runtime::traits::SaturatedConversion;
 use sp_std::vec::Vec;
@@ -17,17 +17,29 @@ use serde::{Deserialize, Serialize};
 #[cfg(test)]
 mod mock;
 
+#[cfg(test)]
+mod mock_accounts;
+
 #[cfg(test)]
 mod tests;
 
+#[cfg(test)]
+mod fuzz;
+
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
+
+mod quota;
+pub use quota::{CheckVaccineQuota, QUOTA_EXHAUSTED};
+
 #[frame_support::pallet]
 pub mod pallet {
 
 	pub use super::*;
 	pub type VacId = Vec<u8>;
+	pub type LotNumber = Vec<u8>;
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
@@ -35,10 +47,26 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type MaxListSize: Get<u32>;
 		type UnixTime: UnixTime;
-		type AccountInfo: AccountPallet;
+		type AccountInfo: AccountPallet<Self::AccountId>;
+		/// Length in blocks of the window the vaccine call quotas are counted in.
+		#[pallet::constant]
+		type QuotaWindow: Get<Self::BlockNumber>;
+		/// Calls per window for a role without a quota of its own.
+		#[pallet::constant]
+		type DefaultQuota: Get<u32>;
+		/// Maximum number of vials registered in one lot.
+		#[pallet::constant]
+		type MaxLotSize: Get<u32>;
+		/// Maximum number of doses in a vial, bounds the users a recall notifies per vial.
+		#[pallet::constant]
+		type MaxDosesPerVial: Get<u32>;
+		/// Maximum number of vials of a lot recalled in one call, a lot is recalled in batches.
+		#[pallet::constant]
+		type MaxRecallBatch: Get<u32>;
 	}
 
 	pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+	pub type VaccineInfoOf<T> = VaccineInfo<BoundedVec<RoleId, <T as Config>::MaxListSize>>;
 
 	#[derive(Decode, Encode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -57,6 +85,53 @@ pub mod pallet {
 		Received,
 		Usable,
 		Used,
+		// held back by its holder, e.g. after a break of the cold chain
+		Quarantined,
+		Recalled,
+		Destroyed,
+		Expired,
+	}
+
+	impl Default for VacStatus {
+		fn default() -> Self {
+			VacStatus::Manufactured
+		}
+	}
+
+	impl VacStatus {
+		/// Whether the vial is still in the supply chain.
+		pub fn in_circulation(&self) -> bool {
+			matches!(self, Self::Manufactured | Self::Shipped | Self::Received | Self::Usable)
+		}
+
+		/// Whether the transition table lets a vial move from `self` to `next`.
+		pub fn can_become(&self, next: &VacStatus) -> bool {
+			use VacStatus::*;
+			match (self, next) {
+				(Manufactured, Shipped) |
+				// sent to another buyer before it was received
+				(Shipped, Shipped | Received) |
+				// sold on by a distributer
+				(Received, Shipped) |
+				// the vial is `Used` once its last dose is handed out
+				(Received | Usable, Usable | Used) => true,
+				// `release_vaccine` restores the status from before the quarantine
+				(from, Quarantined) => from.in_circulation(),
+				// a recall concerns the doses already given as well
+				(from, Recalled) => *from != Recalled,
+				(from, Expired) => from.in_circulation() || *from == Quarantined,
+				(from, Destroyed) => *from != Destroyed,
+				_ => false,
+			}
+		}
+	}
+
+	/// Vials pulled out of circulation by a recall.
+	#[derive(Decode, Encode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum RecallTarget {
+		Vial(VacId),
+		Lot(LotNumber),
 	}
 
 	#[derive(Decode, Encode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, Default)]
@@ -72,7 +147,24 @@ pub mod pallet {
 		pub vac_type_id: Option<VacType>,
 		pub max_inoculations_number: u32,
 		pub inoculation_count: u32,
-		//pub status: Option<VacStatus>,
+		pub status: VacStatus,
+		// lot the vial was registered in, none for a vial registered on its own
+		pub lot: Option<LotNumber>,
+		// unix time in milliseconds from which the vial may no longer be used, none for vials
+		// registered before expiry dates were recorded
+		pub expires_at: Option<u64>,
+	}
+
+	/// Vials released together by a manufacture.
+	#[derive(Decode, Encode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct LotInfo {
+		pub manufacture_id: RoleId,
+		pub vac_type: VacType,
+		// unix time in milliseconds
+		pub manufactured_at: u64,
+		// vials `<lot number>-1` to `<lot number>-<quantity>`
+		pub quantity: u32,
 	}
 
 	#[derive(
@@ -83,18 +175,25 @@ pub mod pallet {
 		pub user_id: RoleId,
 		pub vac_list: BoundedIndexList,
 		pub inoculation_count: u32,
+		// vaccines of `vac_list` which were recalled since
+		pub recalled: BoundedIndexList,
 	}
 
 	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 	#[scale_info(bounds(), skip_type_params(T))]
 	#[codec(mel_bound())]
-	pub struct MovingInfo<T> {
+	pub struct MovingInfo<T: Config> {
 		pub vac_id: VacId,
 		pub from: Option<RoleId>,
 		pub to: Option<RoleId>,
 		pub time: Option<u64>,
 		pub status: Option<VacStatus>,
 		pub phantom: sp_std::marker::PhantomData<T>,
+		// parent organisations when `from` or `to` is a sub-account
+		pub from_org: Option<RoleId>,
+		pub to_org: Option<RoleId>,
+		// principal and operator key when a delegate signed for the acting party
+		pub delegation: Option<(RoleId, T::AccountId)>,
 	}
 
 	impl<T: Config> MovingInfo<T> {
@@ -103,20 +202,39 @@ pub mod pallet {
 			from: Option<RoleId>,
 			to: Option<RoleId>,
 			status: Option<VacStatus>,
+			delegation: Option<(RoleId, T::AccountId)>,
 		) -> Self {
+			let from_org = from.as_ref().and_then(T::AccountInfo::parent_of);
+			let to_org = to.as_ref().and_then(T::AccountInfo::parent_of);
 			MovingInfo {
 				vac_id,
 				from,
 				to,
-				time: Some(T::UnixTime::now().as_millis().saturated_into::<u64>()),
+				time: Some(Pallet::<T>::now()),
 				status,
 				phantom: Default::default(),
+				from_org,
+				to_org,
+				delegation,
 			}
 		}
 	}
 
+	/// Vaccine calls made for a role ID in the current quota window.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct QuotaUsage<BlockNumber> {
+		pub used: u32,
+		pub quota: u32,
+		// first block of the next window
+		pub resets_at: BlockNumber,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -131,6 +249,22 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	// lot number => LotInfo struct
+	#[pallet::storage]
+	#[pallet::getter(fn lots)]
+	pub type Lots<T: Config> = StorageMap<_, Blake2_128Concat, LotNumber, LotInfo, OptionQuery>;
+
+	// lot number => vials of the lot recalled so far, from the first
+	#[pallet::storage]
+	#[pallet::getter(fn lot_recalls)]
+	pub type LotRecalls<T: Config> = StorageMap<_, Blake2_128Concat, LotNumber, u32, OptionQuery>;
+
+	// (holder Role ID, vaccine ID) => expiry of the vials the role ID owns
+	#[pallet::storage]
+	#[pallet::getter(fn held_vials)]
+	pub type HeldVials<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, RoleId, Blake2_128Concat, VacId, u64, OptionQuery>;
+
 	// vaccine ID => MovingInfo struct
 	#[pallet::storage]
 	#[pallet::getter(fn ownership_tracking)]
@@ -158,6 +292,27 @@ pub mod pallet {
 	#[pallet::getter(fn vaccine_type)]
 	pub type VaccineType<T: Config> = StorageValue<_, Vec<VacType>, ValueQuery>;
 
+	// vaccine type => doses in a vial of the type
+	#[pallet::storage]
+	#[pallet::getter(fn doses_per_vial)]
+	pub type DosesPerVial<T: Config> = StorageMap<_, Blake2_128Concat, VacType, u32, OptionQuery>;
+
+	// Role => vaccine calls per quota window
+	#[pallet::storage]
+	#[pallet::getter(fn role_quotas)]
+	pub type RoleQuotas<T: Config> = StorageMap<_, Blake2_128Concat, Role, u32, OptionQuery>;
+
+	// Role ID => vaccine calls per quota window, overrides the quotas of its roles
+	#[pallet::storage]
+	#[pallet::getter(fn role_id_quotas)]
+	pub type RoleIdQuotas<T: Config> = StorageMap<_, Blake2_128Concat, RoleId, u32, OptionQuery>;
+
+	// Role ID => (quota window, vaccine calls made in the window)
+	#[pallet::storage]
+	#[pallet::getter(fn quota_used)]
+	pub type QuotaUsed<T: Config> =
+		StorageMap<_, Blake2_128Concat, RoleId, (T::BlockNumber, u32), OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -168,6 +323,18 @@ pub mod pallet {
 		VaccineApproved(VacId, RoleId),
 		HadVaccination(VacId, RoleId),
 		RegisterVaccineType,
+		/// [role, calls per window, none for the default]
+		RoleQuotaSet(Role, Option<u32>),
+		/// [role ID, calls per window, none for the quota of its roles]
+		RoleIdQuotaSet(RoleId, Option<u32>),
+		/// [lot number, manufacture, quantity]
+		LotRegistered(LotNumber, RoleId, u32),
+		/// [vaccine ID, new status]
+		VaccineStatusChanged(VacId, VacStatus),
+		/// [recalled vials, recaller, number of vials recalled]
+		Recalled(RecallTarget, RoleId, u32),
+		/// a user was handed a dose of a recalled vial [vaccine ID, user]
+		RecallNotice(VacId, RoleId),
 	}
 
 	// Errors inform users that something went wrong.
@@ -185,28 +352,72 @@ pub mod pallet {
 		VaccineTypeIsRegistered,
 		ManuCanNotCreateVaccine,
 		FailToPush,
+		/// The vaccine has no owner recorded.
+		NotFoundOwner,
+		/// The vaccine has not been sent to a buyer yet.
+		NotFoundBuyer,
+		/// The vaccine passport of the user does not exist.
+		NotFoundPassport,
+		/// A lot needs a number and between one and `MaxLotSize` vials.
+		InvalidLot,
+		/// A lot with the number is already registered.
+		LotIsRegistered,
+		/// The vaccine is past its expiry date.
+		VaccineExpired,
+		/// The status of the vaccine can not change to the requested one.
+		InvalidStatusTransition,
+		/// The vaccine is held back in quarantine.
+		VaccineQuarantined,
+		/// The vaccine was recalled.
+		VaccineRecalled,
+		/// The vaccine was destroyed.
+		VaccineDestroyed,
+		/// The vaccine is not in quarantine.
+		VaccineNotQuarantined,
+		/// The vaccine is not past its expiry date yet.
+		VaccineNotExpired,
+		/// Only system managers and the manufacture of the vials recall them.
+		NotManufacture,
+		/// A vial holds between one and `MaxDosesPerVial` doses.
+		InvalidDoses,
+		/// The sub-account still holds vials, they have to be handed on first.
+		SubAccountHoldsVials,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v1::migrate::<T>()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		// register vaccine type by only sysman
+		// register vaccine type by only sysman, with the doses in a vial of the type
 		// ex) Covid19, Flu ...
 		#[pallet::weight(10_000)]
 		pub fn register_vac_type(
 			origin: OriginFor<T>,
 			sysman: RoleId,
 			vac_type: VacType,
+			doses_per_vial: u32,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			T::AccountInfo::check_owner(&who, &sysman)?;
 
-			// only manufacture
-			T::AccountInfo::check_account(&sysman, Role::SYSMAN)?;
+			// only sysman
+			T::AccountInfo::has_permission(&sysman, Capability::RegisterVaccineType)?;
+			ensure!(
+				doses_per_vial > 0 && doses_per_vial <= T::MaxDosesPerVial::get(),
+				Error::<T>::InvalidDoses
+			);
 
 			VaccineType::<T>::try_mutate(|vac_type_list| -> DispatchResult {
 				if vac_type_list.contains(&vac_type) {
 					return Err(Error::<T>::VaccineTypeIsRegistered)?
 				} else {
 					// Emit an event.
+					<DosesPerVial<T>>::insert(&vac_type, doses_per_vial);
 					vac_type_list.push(vac_type);
 					Self::deposit_event(Event::RegisterVaccineType);
 					Ok(())
@@ -224,11 +435,13 @@ pub mod pallet {
 			manufacture: RoleId,
 			vac_id: VacId,
 			vac_type: VacType,
+			expires_at: u64,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			let delegation = Self::ensure_signer(&who, &manufacture, Capability::RegisterVaccine)?;
 
-			// only manufacture
-			T::AccountInfo::check_account(&manufacture, Role::VM)?;
+			// only manufacture or its sites
+			T::AccountInfo::has_permission(&manufacture, Capability::RegisterVaccine)?;
 			// confirm exist vaccine type
 			// ensure!(<VaccineType<T>>::contains_key(vac_type_id.unwrap()),
 			// Error::<T>::NotRegisteredVaccineType);
@@ -238,6 +451,8 @@ pub mod pallet {
 				<VaccineType<T>>::get().contains(&vac_type),
 				Error::<T>::ManuCanNotCreateVaccine
 			);
+			let doses = Self::doses_of(&vac_type)?;
+			ensure!(Self::now() < expires_at, Error::<T>::VaccineExpired);
 			match Vaccines::<T>::try_get(&vac_id) {
 				Ok(_) => return Err(Error::<T>::VaccineIsRegistered)?,
 				Err(_) => {
@@ -249,12 +464,15 @@ pub mod pallet {
 						vao_list: Default::default(),
 						buy_confirm: false,
 						vac_type_id: Some(vac_type),
-						max_inoculations_number: 8,
+						max_inoculations_number: doses,
 						inoculation_count: 0,
-						//status: Some(VacStatus::Manufactured),
+						status: VacStatus::Manufactured,
+						lot: None,
+						expires_at: Some(expires_at),
 					};
 					// Update storage.
 					<Vaccines<T>>::insert(&vac_id, vac_info);
+					<HeldVials<T>>::insert(&manufacture, &vac_id, expires_at);
 				},
 			};
 			Self::transfer_onwership(
@@ -262,6 +480,7 @@ pub mod pallet {
 				Some(manufacture),
 				None,
 				Some(VacStatus::Manufactured),
+				delegation,
 			)?;
 
 			// Emit an event.
@@ -270,6 +489,82 @@ pub mod pallet {
 			Ok(())
 		}
 
+		// register all vials of a lot by only manufacture, they get the IDs `<lot number>-1` to
+		// `<lot number>-<quantity>`
+		// each vial is checked to be free, then written with its holder and tracking
+		#[pallet::weight(
+			T::DbWeight::get()
+				.reads_writes(1, 3)
+				.saturating_mul(*quantity as u64)
+				.saturating_add(10_000)
+		)]
+		pub fn register_lot(
+			origin: OriginFor<T>,
+			manufacture: RoleId,
+			lot_number: LotNumber,
+			manufactured_at: u64,
+			quantity: u32,
+			vac_type: VacType,
+			expires_at: u64,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let delegation = Self::ensure_signer(&who, &manufacture, Capability::RegisterVaccine)?;
+
+			// only manufacture or its sites
+			T::AccountInfo::has_permission(&manufacture, Capability::RegisterVaccine)?;
+			ensure!(
+				<VaccineType<T>>::get().contains(&vac_type),
+				Error::<T>::ManuCanNotCreateVaccine
+			);
+			let doses = Self::doses_of(&vac_type)?;
+			ensure!(
+				!lot_number.is_empty() && quantity > 0 && quantity <= T::MaxLotSize::get(),
+				Error::<T>::InvalidLot
+			);
+			ensure!(Self::now() < expires_at, Error::<T>::VaccineExpired);
+			ensure!(!<Lots<T>>::contains_key(&lot_number), Error::<T>::LotIsRegistered);
+			// nothing is written unless every vial ID is free
+			let vials = Self::vial_ids(&lot_number, quantity);
+			ensure!(
+				vials.iter().all(|vac_id| !<Vaccines<T>>::contains_key(vac_id)),
+				Error::<T>::VaccineIsRegistered
+			);
+
+			for vac_id in vials {
+				let vac_info = VaccineInfo::<BoundedVec<RoleId, T::MaxListSize>> {
+					vac_id: Some(vac_id.clone()),
+					manufacture_id: Some(manufacture.clone()),
+					owner_id: Some(manufacture.clone()),
+					vac_type_id: Some(vac_type.clone()),
+					max_inoculations_number: doses,
+					lot: Some(lot_number.clone()),
+					expires_at: Some(expires_at),
+					..Default::default()
+				};
+				<Vaccines<T>>::insert(&vac_id, vac_info);
+				<HeldVials<T>>::insert(&manufacture, &vac_id, expires_at);
+				// tracked like a single registration, with one event for the whole lot
+				let tracking = MovingInfo::<T>::new(
+					vac_id.clone(),
+					Some(manufacture.clone()),
+					None,
+					Some(VacStatus::Manufactured),
+					delegation.clone(),
+				);
+				<OwnershipTracking<T>>::append(&vac_id, tracking);
+			}
+			let lot = LotInfo {
+				manufacture_id: manufacture.clone(),
+				vac_type,
+				manufactured_at,
+				quantity,
+			};
+			<Lots<T>>::insert(&lot_number, lot);
+
+			Self::deposit_event(Event::LotRegistered(lot_number, manufacture, quantity));
+			Ok(())
+		}
+
 		// transfer vaccine by only manufacture and distributer
 		#[pallet::weight(10_000)]
 		pub fn transfer_vaccine(
@@ -278,11 +573,12 @@ pub mod pallet {
 			buyer_id: RoleId,
 			vac_id: VacId,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			let delegation = Self::ensure_signer(&who, &sender, Capability::TransferVaccine)?;
 
-			// only manufacture or distributer
-			T::AccountInfo::check_union(&sender, Role::VM, Role::VAD)?;
-			T::AccountInfo::check_union(&buyer_id, Role::VM, Role::VAD)?;
+			// only manufacture or distributer, or their sites
+			T::AccountInfo::has_permission(&sender, Capability::TransferVaccine)?;
+			T::AccountInfo::has_permission(&buyer_id, Capability::ReceiveVaccine)?;
 
 			// confirm exist vaccine
 			ensure!(<Vaccines<T>>::contains_key(&vac_id), Error::<T>::NotRegisteredVaccine);
@@ -291,18 +587,19 @@ pub mod pallet {
 			ensure!(sender != buyer_id, Error::<T>::TransferByMyself);
 
 			// vaccine info のownerがsenderか確認
-			let vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
-			let owner_id = vac_info.owner_id.unwrap();
+			let vac_info = <Vaccines<T>>::get(&vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+			let owner_id = vac_info.owner_id.clone().ok_or(Error::<T>::NotFoundOwner)?;
 			ensure!(owner_id == sender, Error::<T>::WrongVaccineOwner);
+			Self::ensure_not_expired(&vac_info)?;
 			// confirm vaccine not used
 			let count = vac_info.inoculation_count;
 			ensure!(count == 0, Error::<T>::VaccineAlreadyUsed);
 
 			// structとstorageの更新
-			let mut new_vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
+			let mut new_vac_info = vac_info;
+			Self::set_status(&mut new_vac_info, VacStatus::Shipped)?;
 			new_vac_info.buyer_id = Some(buyer_id.clone());
 			new_vac_info.buy_confirm = false;
-			//new_vac_info.status = Some(VacStatus::Shipped);
 			<Vaccines<T>>::insert(&vac_id, new_vac_info);
 
 			Self::transfer_onwership(
@@ -310,6 +607,7 @@ pub mod pallet {
 				Some(sender),
 				Some(buyer_id),
 				Some(VacStatus::Shipped),
+				delegation,
 			)?;
 			// Emit an event.
 			Self::deposit_event(Event::TransferVaccine(vac_id));
@@ -325,33 +623,39 @@ pub mod pallet {
 			sender: RoleId,
 			vac_id: VacId,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			let delegation = Self::ensure_signer(&who, &receiver, Capability::ReceiveVaccine)?;
 
 			// confirm exist vaccine
 			ensure!(<Vaccines<T>>::contains_key(&vac_id), Error::<T>::NotRegisteredVaccine);
 
-			// only manufacture or distributer
-			T::AccountInfo::check_union(&receiver, Role::VM, Role::VAD)?;
+			// only manufacture or distributer, or their sites
+			T::AccountInfo::has_permission(&receiver, Capability::ReceiveVaccine)?;
 
 			// only specified receiver
-			let vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
-			let buyer_id = vac_info.buyer_id.unwrap();
+			let vac_info = <Vaccines<T>>::get(&vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+			let buyer_id = vac_info.buyer_id.clone().ok_or(Error::<T>::NotFoundBuyer)?;
 			ensure!(receiver == buyer_id, Error::<T>::NotVaccineBuyer);
 			// confirm vaccine not used
 			let count = vac_info.inoculation_count;
 			ensure!(count == 0, Error::<T>::VaccineAlreadyUsed);
 			// confirm vaccine will not transfer
-			let confirmation = <Vaccines<T>>::get(&vac_id).unwrap().buy_confirm;
+			let confirmation = vac_info.buy_confirm;
 			ensure!(!confirmation, Error::<T>::VaccineAlreadyMine);
 			// confirm correct vaccine owner
-			let owner = <Vaccines<T>>::get(&vac_id).unwrap().owner_id.unwrap();
+			let owner = vac_info.owner_id.clone().ok_or(Error::<T>::NotFoundOwner)?;
 			ensure!(owner == sender, Error::<T>::WrongVaccineOwner);
+			Self::ensure_not_expired(&vac_info)?;
 
 			// update struct and storage
-			let mut new_vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
+			let mut new_vac_info = vac_info;
+			Self::set_status(&mut new_vac_info, VacStatus::Received)?;
 			new_vac_info.owner_id = Some(receiver.clone());
 			new_vac_info.buy_confirm = true;
-			//new_vac_info.status = Some(VacStatus::Received);
+			<HeldVials<T>>::remove(&sender, &vac_id);
+			if let Some(expires_at) = new_vac_info.expires_at {
+				<HeldVials<T>>::insert(&receiver, &vac_id, expires_at);
+			}
 			<Vaccines<T>>::insert(&vac_id, new_vac_info);
 
 			Self::transfer_onwership(
@@ -359,6 +663,7 @@ pub mod pallet {
 				Some(sender.clone()),
 				Some(receiver.clone()),
 				Some(VacStatus::Received),
+				delegation,
 			)?;
 
 			// Emit an event.
@@ -374,23 +679,24 @@ pub mod pallet {
 			organization: RoleId,
 			vac_id: VacId,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			Self::ensure_signer(&who, &organization, Capability::ApproveVaccine)?;
 
 			// only approved organization
-			T::AccountInfo::check_account(&organization, Role::VAO)?;
+			T::AccountInfo::has_permission(&organization, Capability::ApproveVaccine)?;
 			// confirm exist vaccine
 			ensure!(<Vaccines<T>>::contains_key(&vac_id), Error::<T>::NotRegisteredVaccine);
 			//TODO: confirm dont double approve by one organization
 
 			// confirm vaccine not used
-			let vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
+			let vac_info = <Vaccines<T>>::get(&vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
 			let count = vac_info.inoculation_count;
 			ensure!(count == 0, Error::<T>::VaccineAlreadyUsed);
 
 			// upddate struct(vao list)
-			let mut new_vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
+			let mut new_vac_info = vac_info;
 			let result = new_vac_info.vao_list.try_push(organization.clone());
-			if let Err(_) = result {
+			if result.is_err() {
 				return Err(Error::<T>::FailToPush)?
 			}
 			// Update storage.
@@ -410,10 +716,11 @@ pub mod pallet {
 			user_id: RoleId,
 			vac_id: VacId,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			let delegation = Self::ensure_signer(&who, &sender, Capability::AdministerVaccine)?;
 
-			// only distributer
-			T::AccountInfo::check_account(&sender, Role::VAD)?;
+			// only distributer or its clinics
+			T::AccountInfo::has_permission(&sender, Capability::AdministerVaccine)?;
 
 			// confirm exist vaccine
 			ensure!(<Vaccines<T>>::contains_key(&vac_id), Error::<T>::NotRegisteredVaccine);
@@ -425,21 +732,31 @@ pub mod pallet {
 			ensure!(sender != user_id, Error::<T>::TransferByMyself);
 
 			// vaccine info のownerがsenderか確認
-			let vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
-			let owner_id = vac_info.owner_id.unwrap();
+			let vac_info = <Vaccines<T>>::get(&vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+			let owner_id = vac_info.owner_id.clone().ok_or(Error::<T>::NotFoundOwner)?;
 			ensure!(owner_id == sender, Error::<T>::WrongVaccineOwner);
+			Self::ensure_not_expired(&vac_info)?;
 
 			// structとstorageの更新
-			let mut new_vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
+			let mut new_vac_info = vac_info;
 			new_vac_info.buyer_id = Some(user_id.clone());
 			new_vac_info.buy_confirm = false;
-			//new_vac_info.status = Some(VacStatus::Usable);
-			// confirm inoculation count dont reach max number
+			// confirm inoculation count dont reach the doses of the vial, set from its type
 			ensure!(
 				new_vac_info.inoculation_count < new_vac_info.max_inoculations_number,
 				Error::<T>::ExceedMaxShotNumber
 			);
 			new_vac_info.inoculation_count += 1;
+			let status = if new_vac_info.inoculation_count < new_vac_info.max_inoculations_number {
+				VacStatus::Usable
+			} else {
+				VacStatus::Used
+			};
+			Self::set_status(&mut new_vac_info, status)?;
+			// nothing is left in a used vial for its holder to look after
+			if new_vac_info.status == VacStatus::Used {
+				<HeldVials<T>>::remove(&sender, &vac_id);
+			}
 			<Vaccines<T>>::insert(&vac_id, new_vac_info);
 
 			// register vaccine is used
@@ -450,6 +767,7 @@ pub mod pallet {
 				Some(sender),
 				Some(user_id),
 				Some(VacStatus::Usable),
+				delegation,
 			)?;
 			// Emit an event.
 			Self::deposit_event(Event::TransferVaccine(vac_id));
@@ -465,29 +783,34 @@ pub mod pallet {
 			vac_owner: RoleId,
 			vac_id: VacId,
 		) -> DispatchResult {
-			let _ = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			T::AccountInfo::check_owner(&who, &user)?;
 
 			// confirm exist vaccine
 			ensure!(<Vaccines<T>>::contains_key(&vac_id), Error::<T>::NotRegisteredVaccine);
 
 			// confirm vaccine owner
-			let owner = <Vaccines<T>>::get(&vac_id).unwrap().owner_id.unwrap();
-			let confirmation = <Vaccines<T>>::get(&vac_id).unwrap().buy_confirm;
+			let vac_info = <Vaccines<T>>::get(&vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+			let owner = vac_info.owner_id.clone().ok_or(Error::<T>::NotFoundOwner)?;
+			let confirmation = vac_info.buy_confirm;
 			// only specified receiver
-			let vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
-			let buyer_id = vac_info.clone().buyer_id.unwrap();
+			let buyer_id = vac_info.buyer_id.clone().ok_or(Error::<T>::NotFoundBuyer)?;
 			ensure!(user == buyer_id, Error::<T>::NotVaccineBuyer);
 			// confirm vaccine will not transfer
 			ensure!(!confirmation, Error::<T>::VaccineAlreadyMine);
 			// confirm vaccine correct owner
 			ensure!(owner == vac_owner, Error::<T>::WrongVaccineOwner);
+			Self::ensure_not_expired(&vac_info)?;
+			// the dose was given all the same, its passport entry is flagged instead
+			let recalled =
+				vac_info.status == VacStatus::Recalled || Self::in_recalled_lot(&vac_info);
 
 			// confirm send_final_transfer is sended to me?
 			ensure!(<UsedVaccine<T>>::get(&vac_id, &user), Error::<T>::NotSendFinalTransfer);
 
 			// update struct and storage
 			//let mut new_vac_info = <Vaccines<T>>::get(&vac_id).unwrap();
-			//new_vac_info.status = Some(VacStatus::Used);
+			// the status follows the doses handed out, see `transfer_get_vaccine_right`
 			// delete to multiple shot
 			// new_vac_info.owner_id = Some(user.clone());
 			// new_vac_info.buy_confirm = true;
@@ -495,12 +818,25 @@ pub mod pallet {
 
 			// issuing vaccine passport
 			Self::register_vac_pass(user.clone(), vac_id.clone())?;
+			if recalled {
+				let mut passport =
+					Self::vaccine_passports(&user).ok_or(Error::<T>::NotFoundPassport)?;
+				if !passport.recalled.contains(&vac_id) {
+					let result = passport.recalled.try_push(vac_id.clone());
+					if result.is_err() {
+						return Err(Error::<T>::FailToPush)?
+					}
+					<VaccinePassports<T>>::insert(&user, passport);
+				}
+			}
 
+			// the history of a recalled vial keeps the recall as its last status
 			Self::transfer_onwership(
 				vac_id.clone(),
 				Some(vac_owner.clone()),
 				Some(user.clone()),
-				Some(VacStatus::Used),
+				if recalled { None } else { Some(VacStatus::Used) },
+				None,
 			)?;
 
 			// Emit an event.
@@ -508,17 +844,203 @@ pub mod pallet {
 			// Return a successful DispatchResultWithPostInfo
 			Ok(())
 		}
+
+		// hold back a vial by its owner, e.g. after a break of the cold chain
+		#[pallet::weight(10_000)]
+		pub fn quarantine_vaccine(
+			origin: OriginFor<T>,
+			holder: RoleId,
+			vac_id: VacId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			T::AccountInfo::check_owner(&who, &holder)?;
+
+			let mut vac_info = Self::held_vaccine(&holder, &vac_id)?;
+			Self::set_status(&mut vac_info, VacStatus::Quarantined)?;
+
+			Self::update_status(vac_id, vac_info, Some(holder))
+		}
+
+		// release a vial from quarantine to the status it had before
+		#[pallet::weight(10_000)]
+		pub fn release_vaccine(
+			origin: OriginFor<T>,
+			holder: RoleId,
+			vac_id: VacId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			T::AccountInfo::check_owner(&who, &holder)?;
+
+			let mut vac_info = Self::held_vaccine(&holder, &vac_id)?;
+			ensure!(vac_info.status == VacStatus::Quarantined, Error::<T>::VaccineNotQuarantined);
+			// not a transition, the vial goes back to where it was
+			vac_info.status = Self::status_from_history(
+				&vac_id,
+				vac_info.inoculation_count,
+				vac_info.max_inoculations_number,
+			);
+
+			Self::update_status(vac_id, vac_info, Some(holder))
+		}
+
+		// destroy a vial by its owner, it leaves the stock of the owner for good
+		#[pallet::weight(10_000)]
+		pub fn destroy_vaccine(
+			origin: OriginFor<T>,
+			holder: RoleId,
+			vac_id: VacId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			T::AccountInfo::check_owner(&who, &holder)?;
+
+			let mut vac_info = Self::held_vaccine(&holder, &vac_id)?;
+			Self::set_status(&mut vac_info, VacStatus::Destroyed)?;
+			<HeldVials<T>>::remove(&holder, &vac_id);
+
+			Self::update_status(vac_id, vac_info, Some(holder))
+		}
+
+		// mark a vial past its expiry date as expired, by anyone
+		#[pallet::weight(10_000)]
+		pub fn expire_vaccine(origin: OriginFor<T>, vac_id: VacId) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let mut vac_info =
+				<Vaccines<T>>::get(&vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+			ensure!(Self::ensure_not_expired(&vac_info).is_err(), Error::<T>::VaccineNotExpired);
+			Self::set_status(&mut vac_info, VacStatus::Expired)?;
+			// it stays listed to its owner until destroyed
+			let owner = vac_info.owner_id.clone();
+
+			Self::update_status(vac_id, vac_info, owner)
+		}
+
+		// recall a vial or a whole lot by a system manager or its manufacture, the users who were
+		// handed a dose are notified and their passports flagged. A lot is out of circulation
+		// from the first call, its vials are recalled `MaxRecallBatch` at a time by calling again
+		// until all are.
+		#[pallet::weight(Pallet::<T>::recall_weight(match target {
+			RecallTarget::Vial(_) => 1,
+			RecallTarget::Lot(_) => T::MaxRecallBatch::get(),
+		}))]
+		pub fn recall(
+			origin: OriginFor<T>,
+			recaller: RoleId,
+			target: RecallTarget,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			T::AccountInfo::check_owner(&who, &recaller)?;
+			let org = T::AccountInfo::has_permission(&recaller, Capability::RecallVaccine)?;
+
+			let (manufacture, vials) = match &target {
+				RecallTarget::Vial(vac_id) => {
+					let vac_info =
+						<Vaccines<T>>::get(vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+					let manufacture =
+						vac_info.manufacture_id.ok_or(Error::<T>::NotRegisteredVaccine)?;
+					(manufacture, Vec::from([vac_id.clone()]))
+				},
+				RecallTarget::Lot(lot_number) => {
+					let lot = <Lots<T>>::get(lot_number).ok_or(Error::<T>::InvalidLot)?;
+					let done = <LotRecalls<T>>::get(lot_number).unwrap_or(0);
+					ensure!(done < lot.quantity, Error::<T>::VaccineRecalled);
+					// the next batch of vials
+					let until = done.saturating_add(T::MaxRecallBatch::get()).min(lot.quantity);
+					let vials = (done + 1..=until)
+						.map(|index| Self::vial_id(lot_number, index))
+						.collect::<Vec<_>>();
+					(lot.manufacture_id, vials)
+				},
+			};
+			// system managers recall any vial
+			if T::AccountInfo::check_account(&recaller, Role::SYSMAN).is_err() {
+				ensure!(
+					T::AccountInfo::organisation_of(&manufacture) == org,
+					Error::<T>::NotManufacture
+				);
+			}
+
+			if let RecallTarget::Lot(lot_number) = &target {
+				<LotRecalls<T>>::mutate(lot_number, |done| {
+					*done = Some(done.unwrap_or(0).saturating_add(vials.len() as u32))
+				});
+			}
+			let mut recalled = 0u32;
+			for vac_id in vials {
+				let vac_info =
+					<Vaccines<T>>::get(&vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+				// passed over, its users were notified before
+				if vac_info.status == VacStatus::Recalled {
+					continue
+				}
+				Self::recall_vial(&vac_id, vac_info)?;
+				recalled += 1;
+			}
+			// a batch of a lot may hold only vials recalled on their own
+			if let RecallTarget::Vial(_) = &target {
+				ensure!(recalled > 0, Error::<T>::VaccineRecalled);
+			}
+
+			Self::deposit_event(Event::Recalled(target, recaller, recalled));
+			Ok(())
+		}
+
+		/// set the number of vaccine calls per window of a role
+		#[pallet::weight(10_000)]
+		pub fn set_role_quota(
+			origin: OriginFor<T>,
+			role: Role,
+			quota: Option<u32>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<RoleQuotas<T>>::set(&role, quota);
+
+			Self::deposit_event(Event::RoleQuotaSet(role, quota));
+			Ok(())
+		}
+
+		/// set the number of vaccine calls per window of a single role ID
+		#[pallet::weight(10_000)]
+		pub fn set_role_id_quota(
+			origin: OriginFor<T>,
+			role_id: RoleId,
+			quota: Option<u32>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<RoleIdQuotas<T>>::set(&role_id, quota);
+
+			Self::deposit_event(Event::RoleIdQuotaSet(role_id, quota));
+			Ok(())
+		}
 	}
 	/* ----------------------------------------------helper function
 	 * ------------------------------------------------- */
 	impl<T: Config> Pallet<T> {
+		/// Check that `who` signs for `principal`, either with its own key or as an operator key
+		/// holding a delegation of `capability`, and return the delegation to record.
+		pub fn ensure_signer(
+			who: &T::AccountId,
+			principal: &RoleId,
+			capability: Capability,
+		) -> Result<Option<(RoleId, T::AccountId)>, DispatchError> {
+			if T::AccountInfo::check_owner(who, principal).is_ok() {
+				return Ok(None)
+			}
+			T::AccountInfo::check_delegate(who, principal, capability)?;
+			Ok(Some((principal.clone(), who.clone())))
+		}
+
 		pub fn transfer_onwership(
 			vac_id: VacId,
 			from: Option<RoleId>,
 			to: Option<RoleId>,
 			status: Option<VacStatus>,
+			delegation: Option<(RoleId, T::AccountId)>,
 		) -> DispatchResult {
-			let time = MovingInfo::<T>::new(vac_id.clone(), from.clone(), to.clone(), status);
+			let time =
+				MovingInfo::<T>::new(vac_id.clone(), from.clone(), to.clone(), status, delegation);
 			//<OwnershipTracking<T>>::insert(&vac_id, &time);
 			OwnershipTracking::<T>::mutate(&vac_id, |trackings| {
 				trackings.push(time);
@@ -529,6 +1051,176 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Move the vial to `next` if the transition table allows it.
+		pub fn set_status(vac_info: &mut VaccineInfoOf<T>, next: VacStatus) -> DispatchResult {
+			// the vials of a lot being recalled are out of circulation before their batch
+			if !matches!(next, VacStatus::Recalled | VacStatus::Destroyed) &&
+				Self::in_recalled_lot(vac_info)
+			{
+				return Err(Error::<T>::VaccineRecalled)?
+			}
+			if !vac_info.status.can_become(&next) {
+				return Err(match vac_info.status {
+					VacStatus::Used => Error::<T>::VaccineAlreadyUsed,
+					VacStatus::Quarantined => Error::<T>::VaccineQuarantined,
+					VacStatus::Recalled => Error::<T>::VaccineRecalled,
+					VacStatus::Destroyed => Error::<T>::VaccineDestroyed,
+					VacStatus::Expired => Error::<T>::VaccineExpired,
+					_ => Error::<T>::InvalidStatusTransition,
+				})?
+			}
+			vac_info.status = next;
+			Ok(())
+		}
+
+		/// Whether the vial belongs to a lot a recall was started for.
+		pub fn in_recalled_lot(vac_info: &VaccineInfoOf<T>) -> bool {
+			vac_info.lot.as_ref().map_or(false, |lot| <LotRecalls<T>>::contains_key(lot))
+		}
+
+		/// Weight of recalling `vials` vials: each is read and written with its tracking, and so
+		/// is the passport of every user handed one of its doses.
+		pub fn recall_weight(vials: u32) -> Weight {
+			let doses = T::MaxDosesPerVial::get() as u64;
+			T::DbWeight::get()
+				.reads_writes(2 + 2 * doses, 2 + doses)
+				.saturating_mul(vials as u64)
+				.saturating_add(10_000)
+		}
+
+		/// Status of a vial as told by its tracking history, quarantines left out.
+		pub fn status_from_history(
+			vac_id: &VacId,
+			inoculation_count: u32,
+			max_inoculations_number: u32,
+		) -> VacStatus {
+			let last = <OwnershipTracking<T>>::get(vac_id)
+				.into_iter()
+				.rev()
+				.filter_map(|tracking| tracking.status)
+				.find(|status| *status != VacStatus::Quarantined);
+			match last {
+				// every dose handed out is recorded, the vial is used once the last one is
+				Some(VacStatus::Usable | VacStatus::Used) =>
+					if inoculation_count < max_inoculations_number {
+						VacStatus::Usable
+					} else {
+						VacStatus::Used
+					},
+				Some(status) => status,
+				None => VacStatus::Manufactured,
+			}
+		}
+
+		/// Mark the vial as recalled, notify the users who were handed a dose and flag it in
+		/// their passports.
+		fn recall_vial(vac_id: &VacId, mut vac_info: VaccineInfoOf<T>) -> DispatchResult {
+			Self::set_status(&mut vac_info, VacStatus::Recalled)?;
+			let owner = vac_info.owner_id.clone();
+			if let Some(owner) = &owner {
+				<HeldVials<T>>::remove(owner, vac_id);
+			}
+			Self::update_status(vac_id.clone(), vac_info, owner)?;
+
+			for (user, _) in <UsedVaccine<T>>::iter_prefix(vac_id) {
+				// an erased user has no passport left, the notice is still given
+				if let Some(mut passport) = <VaccinePassports<T>>::get(&user) {
+					if passport.vac_list.contains(vac_id) && !passport.recalled.contains(vac_id) {
+						let result = passport.recalled.try_push(vac_id.clone());
+						if result.is_err() {
+							return Err(Error::<T>::FailToPush)?
+						}
+						<VaccinePassports<T>>::insert(&user, passport);
+					}
+				}
+				Self::deposit_event(Event::RecallNotice(vac_id.clone(), user));
+			}
+			Ok(())
+		}
+
+		/// Vaccine info of `vac_id`, which has to be owned by `holder`.
+		fn held_vaccine(
+			holder: &RoleId,
+			vac_id: &VacId,
+		) -> Result<VaccineInfoOf<T>, DispatchError> {
+			let vac_info = <Vaccines<T>>::get(vac_id).ok_or(Error::<T>::NotRegisteredVaccine)?;
+			let owner = vac_info.owner_id.clone().ok_or(Error::<T>::NotFoundOwner)?;
+			ensure!(owner == *holder, Error::<T>::WrongVaccineOwner);
+			Ok(vac_info)
+		}
+
+		/// Store the vial with its new status and record the change in its history.
+		fn update_status(
+			vac_id: VacId,
+			vac_info: VaccineInfoOf<T>,
+			holder: Option<RoleId>,
+		) -> DispatchResult {
+			let status = vac_info.status.clone();
+			<Vaccines<T>>::insert(&vac_id, vac_info);
+			Self::transfer_onwership(vac_id.clone(), holder, None, Some(status.clone()), None)?;
+			Self::deposit_event(Event::VaccineStatusChanged(vac_id, status));
+			Ok(())
+		}
+
+		/// Doses in a vial of the registered `vac_type`.
+		pub fn doses_of(vac_type: &VacType) -> Result<u32, DispatchError> {
+			Ok(<DosesPerVial<T>>::get(vac_type).ok_or(Error::<T>::ManuCanNotCreateVaccine)?)
+		}
+
+		/// Unix time in milliseconds.
+		pub fn now() -> u64 {
+			T::UnixTime::now().as_millis().saturated_into::<u64>()
+		}
+
+		/// Passes unless the vial is past its expiry date.
+		pub fn ensure_not_expired(vac_info: &VaccineInfoOf<T>) -> DispatchResult {
+			if let Some(expires_at) = vac_info.expires_at {
+				ensure!(Self::now() < expires_at, Error::<T>::VaccineExpired);
+			}
+			Ok(())
+		}
+
+		/// Vials owned by `holder` which expire within `within` milliseconds from now, expired
+		/// ones included, with their expiry and the soonest first.
+		pub fn expiring_vials(holder: &RoleId, within: u64) -> Vec<(VacId, u64)> {
+			let until = Self::now().saturating_add(within);
+			let mut vials: Vec<(VacId, u64)> = <HeldVials<T>>::iter_prefix(holder)
+				.filter(|(_, expires_at)| *expires_at <= until)
+				.collect();
+			vials.sort_by_key(|(_, expires_at)| *expires_at);
+			vials
+		}
+
+		/// IDs of the vials of a lot of `quantity` vials numbered `lot_number`.
+		pub fn vial_ids(lot_number: &[u8], quantity: u32) -> Vec<VacId> {
+			(1..=quantity).map(|index| Self::vial_id(lot_number, index)).collect()
+		}
+
+		/// ID of the vial `index`, counted from one, of the lot numbered `lot_number`.
+		pub fn vial_id(lot_number: &[u8], index: u32) -> VacId {
+			let mut vac_id = lot_number.to_vec();
+			vac_id.push(b'-');
+			// decimal digits of the index
+			let mut digits = Vec::new();
+			let mut rest = index;
+			loop {
+				digits.push(b'0' + (rest % 10) as u8);
+				rest /= 10;
+				if rest == 0 {
+					break
+				}
+			}
+			vac_id.extend(digits.iter().rev());
+			vac_id
+		}
+
+		/// IDs of the vials of the registered lot `lot_number`.
+		pub fn vials_of_lot(lot_number: &[u8]) -> Vec<VacId> {
+			<Lots<T>>::get(lot_number)
+				.map(|lot| Self::vial_ids(lot_number, lot.quantity))
+				.unwrap_or_default()
+		}
+
 		pub fn register_vac_pass(registrant: RoleId, vac_id: VacId) -> DispatchResult {
 			match VaccinePassports::<T>::try_get(&registrant) {
 				Ok(_) => {},
@@ -537,6 +1229,7 @@ pub mod pallet {
 						user_id: registrant.clone(),
 						vac_list: Default::default(),
 						inoculation_count: 0,
+						recalled: Default::default(),
 					};
 
 					// Update storage.
@@ -545,13 +1238,14 @@ pub mod pallet {
 			};
 
 			// register vaccine list
-			let mut passport = Self::vaccine_passports(&registrant).unwrap();
+			let mut passport =
+				Self::vaccine_passports(&registrant).ok_or(Error::<T>::NotFoundPassport)?;
 
 			let result = passport.vac_list.try_push(vac_id);
-			if let Err(_) = result {
+			if result.is_err() {
 				return Err(Error::<T>::FailToPush)?
 			}
-			passport.inoculation_count += 1;
+			passport.inoculation_count = passport.inoculation_count.saturating_add(1);
 
 			// Update storage.
 			<VaccinePassports<T>>::insert(&registrant, passport);
@@ -559,6 +1253,106 @@ pub mod pallet {
 			Ok(())
 		}
 	}
+
+	impl<T: Config> Pallet<T> {
+		/// Vaccine calls per window of `role_id`, its own quota or the most generous one of the
+		/// roles of its organisation.
+		pub fn quota_of(role_id: &RoleId) -> u32 {
+			if let Some(quota) = <RoleIdQuotas<T>>::get(role_id) {
+				return quota
+			}
+			T::AccountInfo::roles_of(&T::AccountInfo::organisation_of(role_id))
+				.iter()
+				.map(|role| <RoleQuotas<T>>::get(role).unwrap_or_else(T::DefaultQuota::get))
+				.max()
+				.unwrap_or_else(T::DefaultQuota::get)
+		}
+
+		/// Vaccine calls made for `role_id` in the current window and its quota.
+		pub fn quota_usage(role_id: &RoleId) -> QuotaUsage<T::BlockNumber> {
+			let current = Self::quota_window();
+			let used = match <QuotaUsed<T>>::get(role_id) {
+				Some((window, used)) if window == current => used,
+				_ => 0,
+			};
+			QuotaUsage {
+				used,
+				quota: Self::quota_of(role_id),
+				resets_at: (current + 1u32.into()) * Self::quota_window_length(),
+			}
+		}
+
+		/// Count a vaccine call made for `role_id` in the current window.
+		pub fn note_quota_use(role_id: &RoleId) {
+			let current = Self::quota_window();
+			<QuotaUsed<T>>::mutate(role_id, |used| match used {
+				Some((window, count)) if *window == current => *count = count.saturating_add(1),
+				_ => *used = Some((current, 1)),
+			});
+		}
+
+		fn quota_window() -> T::BlockNumber {
+			<frame_system::Pallet<T>>::block_number() / Self::quota_window_length()
+		}
+
+		fn quota_window_length() -> T::BlockNumber {
+			T::QuotaWindow::get().max(1u32.into())
+		}
+	}
+
+	impl<T: Config> Call<T> {
+		/// Role ID the call is made for, its quota is charged.
+		pub fn quota_subject(&self) -> Option<&RoleId> {
+			match self {
+				Call::register_vac_type { sysman, .. } => Some(sysman),
+				Call::register_vac_info { manufacture, .. } => Some(manufacture),
+				Call::register_lot { manufacture, .. } => Some(manufacture),
+				Call::transfer_vaccine { sender, .. } => Some(sender),
+				Call::receive_vaccine { receiver, .. } => Some(receiver),
+				Call::approve_vaccine { organization, .. } => Some(organization),
+				Call::transfer_get_vaccine_right { sender, .. } => Some(sender),
+				Call::confirm_vaccine { user, .. } => Some(user),
+				Call::quarantine_vaccine { holder, .. } => Some(holder),
+				Call::release_vaccine { holder, .. } => Some(holder),
+				Call::destroy_vaccine { holder, .. } => Some(holder),
+				Call::recall { recaller, .. } => Some(recaller),
+				_ => None,
+			}
+		}
+
+		/// Capability an operator key needs a delegation of to make the call for a role ID.
+		pub fn delegated_capability(&self) -> Option<Capability> {
+			match self {
+				Call::register_vac_info { .. } | Call::register_lot { .. } =>
+					Some(Capability::RegisterVaccine),
+				Call::transfer_vaccine { .. } => Some(Capability::TransferVaccine),
+				Call::receive_vaccine { .. } => Some(Capability::ReceiveVaccine),
+				Call::approve_vaccine { .. } => Some(Capability::ApproveVaccine),
+				Call::transfer_get_vaccine_right { .. } => Some(Capability::AdministerVaccine),
+				_ => None,
+			}
+		}
+	}
+
+	impl<T: Config> OnErasure for Pallet<T> {
+		// the history in `OwnershipTracking` and `UsedVaccine` only refers to the role ID and
+		// is kept
+		fn on_erasure(who: &RoleId) {
+			<VaccinePassports<T>>::remove(who);
+		}
+	}
+
+	impl<T: Config> OnSubAccountRemoval for Pallet<T> {
+		// used, destroyed and recalled vials have left the stock of their holder, expired and
+		// quarantined ones stay in it until destroyed
+		fn can_remove(sub: &RoleId) -> DispatchResult {
+			ensure!(
+				<HeldVials<T>>::iter_prefix(sub).next().is_none(),
+				Error::<T>::SubAccountHoldsVials
+			);
+			Ok(())
+		}
+	}
 }
 
 #[cfg(feature = "std")]
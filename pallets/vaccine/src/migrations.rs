@@ -0,0 +1,158 @@
+//! Storage migrations for the vaccine pallet.
+
+use super::*;
+use frame_support::{
+	migration::{storage_iter, storage_key_iter},
+	traits::{PalletInfoAccess, StorageVersion},
+	weights::Weight,
+};
+use pallet_account::migrations::v1::{role_id_of, OldRoleId};
+
+/// From the baseline layout to storage version 1.
+///
+/// Role IDs are re-keyed the way the account pallet re-keys them. The status of every vaccine is
+/// derived from its tracking history, vaccines already on the chain belong to no lot and have no
+/// expiry date.
+pub mod v1 {
+	use super::*;
+
+	/// Doses every vial was registered with before storage version 1.
+	pub const OLD_DOSES_PER_VIAL: u32 = 8;
+
+	/// `VaccineInfo` layout before storage version 1.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+	pub struct OldVaccineInfo {
+		pub vac_id: Option<VacId>,
+		pub manufacture_id: Option<OldRoleId>,
+		pub owner_id: Option<OldRoleId>,
+		pub buyer_id: Option<OldRoleId>,
+		pub vao_list: Vec<OldRoleId>,
+		pub buy_confirm: bool,
+		pub vac_type_id: Option<VacType>,
+		pub max_inoculations_number: u32,
+		pub inoculation_count: u32,
+	}
+
+	/// `MovingInfo` layout before storage version 1.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+	pub struct OldMovingInfo {
+		pub vac_id: VacId,
+		pub from: Option<OldRoleId>,
+		pub to: Option<OldRoleId>,
+		pub time: Option<u64>,
+		pub status: Option<VacStatus>,
+	}
+
+	/// `PassportInfo` layout before storage version 1.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+	pub struct OldPassportInfo {
+		pub user_id: OldRoleId,
+		pub vac_list: Vec<VacId>,
+		pub inoculation_count: u32,
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::get::<Pallet<T>>() >= StorageVersion::new(1) {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		let mut translated = 0u64;
+
+		// the history goes first, the status of the vaccines is derived from it
+		<OwnershipTracking<T>>::translate::<Vec<OldMovingInfo>, _>(|_, old| {
+			translated += 1;
+			// there were no sub-accounts nor delegates before
+			let trackings = old
+				.into_iter()
+				.map(|old| MovingInfo {
+					vac_id: old.vac_id,
+					from: old.from.as_deref().map(role_id_of),
+					to: old.to.as_deref().map(role_id_of),
+					time: old.time,
+					status: old.status,
+					phantom: Default::default(),
+					from_org: None,
+					to_org: None,
+					delegation: None,
+				})
+				.collect();
+			Some(trackings)
+		});
+
+		<Vaccines<T>>::translate::<OldVaccineInfo, _>(|vac_id, old| {
+			translated += 1;
+			let status = Pallet::<T>::status_from_history(
+				&vac_id,
+				old.inoculation_count,
+				old.max_inoculations_number,
+			);
+			let vao_list: Vec<RoleId> = old.vao_list.iter().map(|id| role_id_of(id)).collect();
+			Some(VaccineInfo {
+				vac_id: old.vac_id,
+				manufacture_id: old.manufacture_id.as_deref().map(role_id_of),
+				owner_id: old.owner_id.as_deref().map(role_id_of),
+				buyer_id: old.buyer_id.as_deref().map(role_id_of),
+				// bounded by `MaxListSize` before as well
+				vao_list: vao_list.try_into().unwrap_or_default(),
+				buy_confirm: old.buy_confirm,
+				vac_type_id: old.vac_type_id,
+				max_inoculations_number: old.max_inoculations_number,
+				inoculation_count: old.inoculation_count,
+				status,
+				lot: None,
+				expires_at: None,
+			})
+		});
+
+		let passports: Vec<(OldRoleId, OldPassportInfo)> = storage_key_iter::<
+			OldRoleId,
+			OldPassportInfo,
+			Blake2_128Concat,
+		>(pallet, b"VaccinePassports")
+		.drain()
+		.collect();
+		for (old_id, old) in passports {
+			translated += 1;
+			// nothing was recalled before
+			let passport = PassportInfo {
+				user_id: role_id_of(&old.user_id),
+				vac_list: old.vac_list.try_into().unwrap_or_default(),
+				inoculation_count: old.inoculation_count,
+				recalled: Default::default(),
+			};
+			<VaccinePassports<T>>::insert(role_id_of(&old_id), passport);
+		}
+
+		let used: Vec<(Vec<u8>, bool)> =
+			storage_iter::<bool>(pallet, b"UsedVaccine").drain().collect();
+		for (key, used) in used {
+			translated += 1;
+			if let Some((vac_id, old_id)) = decode_used_vaccine_key(&key) {
+				<UsedVaccine<T>>::insert(vac_id, role_id_of(&old_id), used);
+			}
+		}
+
+		let vac_types = <VaccineType<T>>::get();
+		for vac_type in vac_types.iter() {
+			translated += 1;
+			<DosesPerVial<T>>::insert(vac_type, OLD_DOSES_PER_VIAL);
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("pallet_vaccine: migrated {} entries to storage version 1", translated);
+
+		// the tracking history of every vaccine is read twice
+		T::DbWeight::get().reads_writes(2 * translated + 2, 2 * translated + 1)
+	}
+
+	/// Vaccine ID and legacy role ID of a `UsedVaccine` key without its storage prefix.
+	fn decode_used_vaccine_key(key: &[u8]) -> Option<(VacId, OldRoleId)> {
+		// each key is preceded by its 16 byte blake2 hash
+		let mut input = key.get(16..)?;
+		let vac_id = VacId::decode(&mut input).ok()?;
+		let mut input = input.get(16..)?;
+		let old_id = OldRoleId::decode(&mut input).ok()?;
+		Some((vac_id, old_id))
+	}
+}
@@ -1,7 +1,8 @@
-use crate as pallet_template;
+use crate as pallet_vaccine;
 // use crate as pallet_balances;
 use frame_support::traits::{ConstU16, ConstU32, ConstU64, GenesisBuild};
 use frame_system as system;
+use pallet_account::RoleId;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
@@ -20,7 +21,9 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		Account: pallet_account::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Vaccine: pallet_vaccine::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -63,9 +66,44 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = ();
 }
 
-impl pallet_template::Config for Test {
+impl pallet_timestamp::Config for Test {
+	/// A timestamp: milliseconds since the unix epoch.
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<{ 6000 / 2 }>;
+	type WeightInfo = ();
+}
+
+impl pallet_account::Config for Test {
 	type Event = Event;
 	type MaxListSize = ConstU32<3>;
+	type UnixTime = pallet_timestamp::Pallet<Self>;
+	type ExpiryPeriod = ConstU64<1_000>;
+	type ProposalLifetime = ConstU64<10_000>;
+	type MaxProfileLength = ConstU32<16>;
+	type MaxDocuments = ConstU32<2>;
+	type RecoveryDelay = ConstU64<5_000>;
+	type MaxDelegationPeriod = ConstU64<20_000>;
+	type OnErasure = Vaccine;
+	type OnSubAccountRemoval = Vaccine;
+	type Currency = Balances;
+	type RoleBond = ConstU64<1_000>;
+	type FreeTransactions = ConstU32<2>;
+	type FreeTransactionPeriod = ConstU64<10>;
+	type MaxDidEntries = ConstU32<2>;
+	type MaxDidLength = ConstU32<32>;
+}
+
+impl pallet_vaccine::Config for Test {
+	type Event = Event;
+	type MaxListSize = ConstU32<3>;
+	type UnixTime = pallet_timestamp::Pallet<Self>;
+	type AccountInfo = Account;
+	type QuotaWindow = ConstU64<10>;
+	type DefaultQuota = ConstU32<100>;
+	type MaxLotSize = ConstU32<10>;
+	type MaxDosesPerVial = ConstU32<10>;
+	type MaxRecallBatch = ConstU32<2>;
 }
 
 pub struct ExtBuilder;
@@ -92,8 +130,20 @@ impl ExtBuilder {
 	}
 
 	pub fn set_genesis_account(self) -> sp_io::TestExternalities {
-		let mut t = pallet_template::GenesisConfig::<Test>::default().build_storage().unwrap();
-		pallet_template::GenesisConfig::<Test> { genesis_account: vec![1] }
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![
+				(1, 1000000000000000),
+				(2, 2000000000000000),
+				(3, 3000000000000000),
+				(4, 4000000000000000),
+				(5, 5000000000000000),
+				(6, 6000000000000000),
+			],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		pallet_account::GenesisConfig::<Test> { genesis_account: vec![(role_id(1), 1)] }
 			.assimilate_storage(&mut t)
 			.unwrap();
 
@@ -109,6 +159,11 @@ impl Default for ExtBuilder {
 	}
 }
 
+// Role ID used in tests for the given signing account
+pub fn role_id(who: u64) -> RoleId {
+	RoleId::try_from(format!("00000000-0000-4000-8000-{:012}", who).as_str()).unwrap()
+}
+
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
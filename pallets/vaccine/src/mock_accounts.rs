@@ -0,0 +1,82 @@
+//! Mock runtime answering account queries from `MockAccounts` instead of the account pallet, so
+//! that tests set up roles and sub-accounts directly.
+
+use crate as pallet_vaccine;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use frame_system as system;
+pub use pallet_account::testing::MockAccounts;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		Vaccine: pallet_vaccine::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_timestamp::Config for Test {
+	/// A timestamp: milliseconds since the unix epoch.
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<{ 6000 / 2 }>;
+	type WeightInfo = ();
+}
+
+impl pallet_vaccine::Config for Test {
+	type Event = Event;
+	type MaxListSize = ConstU32<3>;
+	type UnixTime = pallet_timestamp::Pallet<Self>;
+	type AccountInfo = MockAccounts;
+	type QuotaWindow = ConstU64<10>;
+	type DefaultQuota = ConstU32<100>;
+	type MaxLotSize = ConstU32<10>;
+	type MaxDosesPerVial = ConstU32<10>;
+	type MaxRecallBatch = ConstU32<2>;
+}
+
+// Build genesis storage according to the mock runtime, without any account.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,108 @@
+//! Transaction validity check keeping vaccine calls within the quota of the role ID they are
+//! made for, so that a compromised key can not flood the chain.
+
+use super::*;
+use frame_support::traits::IsSubType;
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::marker::PhantomData;
+
+/// Custom `InvalidTransaction` code of a vaccine call beyond the quota of its role ID.
+pub const QUOTA_EXHAUSTED: u8 = 1;
+
+/// Rejects vaccine calls from the transaction pool once the role ID they are made for used up the
+/// calls of the current window, and counts the included ones.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckVaccineQuota<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckVaccineQuota<T> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+
+	fn check(role_id: &RoleId) -> Result<(), TransactionValidityError> {
+		let usage = Pallet::<T>::quota_usage(role_id);
+		if usage.used >= usage.quota {
+			return Err(InvalidTransaction::Custom(QUOTA_EXHAUSTED).into())
+		}
+		Ok(())
+	}
+
+	/// Role ID the call is charged to, when `who` signs for it with the bound key or a
+	/// delegation. Calls signed by anyone else fail on dispatch and must not use up the quota
+	/// of the role ID they name.
+	fn subject<'a>(who: &T::AccountId, call: &'a Call<T>) -> Option<&'a RoleId> {
+		let role_id = call.quota_subject()?;
+		let signs_for = T::AccountInfo::check_owner(who, role_id).is_ok() ||
+			call.delegated_capability().map_or(false, |capability| {
+				T::AccountInfo::check_delegate(who, role_id, capability).is_ok()
+			});
+		signs_for.then(|| role_id)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for CheckVaccineQuota<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for CheckVaccineQuota<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "CheckVaccineQuota")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckVaccineQuota<T>
+where
+	<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "CheckVaccineQuota";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		if let Some(role_id) = call.is_sub_type().and_then(|call| Self::subject(who, call)) {
+			Self::check(role_id)?;
+		}
+		Ok(ValidTransaction::default())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		// failing calls of the role ID count as well, they take block space all the same
+		if let Some(role_id) = call.is_sub_type().and_then(|call| Self::subject(who, call)) {
+			Self::check(role_id)?;
+			Pallet::<T>::note_quota_use(role_id);
+		}
+		Ok(())
+	}
+}
@@ -1,6 +1,8 @@
-use crate as pallet_template;
+use crate as pallet_vaccine;
 use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok, traits::GenesisBuild};
+use frame_support::{assert_noop, assert_ok};
+use pallet_account::Role;
+use pallet_vaccine::{VacId, VacType};
 
 pub const ALICE: u64 = 1;
 pub const BOB: u64 = 2;
@@ -10,242 +12,300 @@ pub const EVE: u64 = 5;
 pub const FRANK: u64 = 6;
 pub const GEORGE: u64 = 7;
 
+// far beyond the time of any test
+const EXPIRES_AT: u64 = 1_000_000;
+// doses in a vial of the vaccine types of the tests
+const DOSES: u32 = 8;
+
+fn vac_id(n: u32) -> VacId {
+	format!("vac-{}", n).into_bytes()
+}
+
+// register and approve an account for the given role
+fn register(who: u64, role: Role) {
+	assert_ok!(Account::register_account(Origin::root(), role_id(who), who, role.clone()));
+	assert_ok!(Account::approve_role(
+		Origin::signed(ALICE),
+		role_id(ALICE),
+		role_id(who),
+		role,
+		None
+	));
+}
+
 // ALICE(1): system manager
 // BOB(2): manufacture
 // CHARLIE(3): VAO
 // DAVE(4): VAD
-// EVE(5): USER
+// EVE(5): VAD
+// FRANK(6): user
+// GEORGE(7): user
+fn setup_accounts() {
+	register(BOB, Role::VM);
+	register(CHARLIE, Role::VAO);
+	register(DAVE, Role::VAD);
+	register(EVE, Role::VAD);
+	register(FRANK, Role::USER);
+	register(GEORGE, Role::USER);
+}
 
 #[test]
 fn start_system() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(TemplateModule::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(TemplateModule::claim_vm(Origin::signed(BOB)));
-		// 3 claimed to be authorized organization
-		assert_ok!(TemplateModule::claim_vao(Origin::signed(CHARLIE)));
-		// 4 claimed to be distributer
-		assert_ok!(TemplateModule::claim_vad(Origin::signed(DAVE)));
-		// 2 is manufacture
-		assert_ok!(TemplateModule::approve_vm(Origin::signed(ALICE), BOB));
-		// 3 is authorized organization
-		assert_ok!(TemplateModule::approve_vao(Origin::signed(ALICE), CHARLIE));
-		// 4 is distributer
-		assert_ok!(TemplateModule::approve_vad(Origin::signed(ALICE), DAVE));
+		setup_accounts();
 		// sysman(1) register vaccine type
-		assert_ok!(TemplateModule::register_vac_type(Origin::signed(ALICE)));
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
 		// VM(2) register vaccine information
-		assert_ok!(TemplateModule::register_vac_info(Origin::signed(BOB), Some(1)));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
 		// VAO approve vaccine
-		assert_ok!(TemplateModule::approve_vaccine(Origin::signed(CHARLIE), Some(1)));
+		assert_ok!(Vaccine::approve_vaccine(Origin::signed(CHARLIE), role_id(CHARLIE), vac_id(1)));
 		// VM transfer vaccine to VAD
-		assert_ok!(TemplateModule::transfer_vaccine(Origin::signed(BOB), DAVE, Some(1)));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
 		// VAD receive vaccine from VM
-		assert_ok!(TemplateModule::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
 		// User have a vaccine in VAD
-		assert_ok!(TemplateModule::transfer_get_vaccine_right(Origin::signed(DAVE), EVE, Some(1)));
-		assert_ok!(TemplateModule::confirm_vaccine(Origin::signed(EVE), Some(DAVE), Some(1)));
-
-		println!("vaccine info: {:?}", TemplateModule::vaccines(1));
-		println!("vaccine passport info: {:?}", TemplateModule::vaccine_passports(EVE));
-	});
-}
-
-// ALICE(1): system manager
-// BOB(2): manufacture
-// CHARLIE(3): user
-
-#[test]
-fn should_cause_error_for_register_role() {
-	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(TemplateModule::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(TemplateModule::claim_vm(Origin::signed(BOB)));
-
-		// claim again after claimed but before approved
-		assert_noop!(TemplateModule::claim_vm(Origin::signed(BOB)), Error::<Test>::AlreadyClaimed);
-
-		// 2 is manufacture
-		assert_ok!(TemplateModule::approve_vm(Origin::signed(ALICE), BOB));
-
-		// claim again after approved
-		assert_noop!(TemplateModule::claim_vm(Origin::signed(BOB)), Error::<Test>::AlreadyApproved);
-
-		// not system manager approve role
-		assert_noop!(
-			TemplateModule::approve_vm(Origin::signed(CHARLIE), BOB),
-			Error::<Test>::NotSysMan
-		);
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::confirm_vaccine(
+			Origin::signed(FRANK),
+			role_id(FRANK),
+			role_id(DAVE),
+			vac_id(1)
+		));
 
-		// approve again after approved
-		assert_noop!(
-			TemplateModule::approve_vm(Origin::signed(ALICE), BOB),
-			Error::<Test>::NotClaimed
-		);
-		// approve entity which does not claim
-		assert_noop!(
-			TemplateModule::approve_vm(Origin::signed(ALICE), CHARLIE),
-			Error::<Test>::NotClaimed
-		);
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().owner_id, Some(role_id(DAVE)));
+		assert_eq!(Vaccine::vaccine_passports(role_id(FRANK)).unwrap().inoculation_count, 1);
 	});
 }
 
 // ALICE(1): system manager
 // BOB(2): manufacture
 // CHARLIE(3): VAO
-// DAVE(4): user
+// DAVE(4): VAD
 #[test]
 fn should_cause_error_for_register_info_about_vaccine() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(TemplateModule::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(TemplateModule::claim_vm(Origin::signed(BOB)));
-		// 3 claimed to be authorized organization
-		assert_ok!(TemplateModule::claim_vao(Origin::signed(CHARLIE)));
-		// 2 is manufacture
-		assert_ok!(TemplateModule::approve_vm(Origin::signed(ALICE), BOB));
-		// 3 is authorized organization
-		assert_ok!(TemplateModule::approve_vao(Origin::signed(ALICE), CHARLIE));
+		setup_accounts();
 
 		// NOT sysman register vaccine type
 		assert_noop!(
-			TemplateModule::register_vac_type(Origin::signed(DAVE)),
-			Error::<Test>::NotSysMan
+			Vaccine::register_vac_type(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				VacType::COVID19,
+				DOSES
+			),
+			pallet_account::Error::<Test>::InvalidRole
 		);
 
 		// sysman(1) register vaccine type
-		assert_ok!(TemplateModule::register_vac_type(Origin::signed(ALICE)));
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
 
-		// NOT manufacture register vaccine type
+		// register same vaccine type twice
 		assert_noop!(
-			TemplateModule::register_vac_info(Origin::signed(DAVE), Some(1)),
-			Error::<Test>::NotManufacture
+			Vaccine::register_vac_type(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				VacType::COVID19,
+				DOSES
+			),
+			Error::<Test>::VaccineTypeIsRegistered
+		);
+
+		// NOT manufacture register vaccine info
+		assert_noop!(
+			Vaccine::register_vac_info(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				vac_id(1),
+				VacType::COVID19,
+				EXPIRES_AT
+			),
+			pallet_account::Error::<Test>::InvalidRole
 		);
 
-		// sysman register not exist vaccine type
+		// manufacture register not exist vaccine type
 		assert_noop!(
-			TemplateModule::register_vac_info(Origin::signed(BOB), Some(2)),
-			Error::<Test>::NotRegisteredVaccineType
+			Vaccine::register_vac_info(
+				Origin::signed(BOB),
+				role_id(BOB),
+				vac_id(1),
+				VacType::FLU,
+				EXPIRES_AT
+			),
+			Error::<Test>::ManuCanNotCreateVaccine
 		);
 
 		//register vaciine info
-		assert_ok!(TemplateModule::register_vac_info(Origin::signed(BOB), Some(1)));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
 
 		// vaccine has been already registered
 		assert_noop!(
-			TemplateModule::register_vac_info(Origin::signed(BOB), Some(1)),
+			Vaccine::register_vac_info(
+				Origin::signed(BOB),
+				role_id(BOB),
+				vac_id(1),
+				VacType::COVID19,
+				EXPIRES_AT
+			),
 			Error::<Test>::VaccineIsRegistered
 		);
 
 		// approve vaccine
-		assert_ok!(TemplateModule::approve_vaccine(Origin::signed(CHARLIE), Some(1)));
+		assert_ok!(Vaccine::approve_vaccine(Origin::signed(CHARLIE), role_id(CHARLIE), vac_id(1)));
 
-		// not VAO register vaciine info
+		// not VAO approve vaccine
 		assert_noop!(
-			TemplateModule::approve_vaccine(Origin::signed(DAVE), Some(1)),
-			Error::<Test>::NotOrganization
+			Vaccine::approve_vaccine(Origin::signed(DAVE), role_id(DAVE), vac_id(1)),
+			pallet_account::Error::<Test>::InvalidRole
 		);
 
-		// VAO register not exist vaciine id
+		// VAO approve not exist vaccine id
 		assert_noop!(
-			TemplateModule::approve_vaccine(Origin::signed(CHARLIE), Some(2)),
+			Vaccine::approve_vaccine(Origin::signed(CHARLIE), role_id(CHARLIE), vac_id(2)),
 			Error::<Test>::NotRegisteredVaccine
 		);
-
-		// TODO: duplicate vaccine approvement
-		// TODO: exceed max list size (3 in mock runtime)
 	});
 }
 
 // ALICE(1): system manager
 // BOB(2): manufacture
-// CHARLIE(3): VAO
 // DAVE(4): VAD
 // EVE(5): VAD
 // FRANK(6): user
-// GEORGE(7):
 #[test]
 fn should_cause_error_for_transfer_vaccine() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(TemplateModule::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(TemplateModule::claim_vm(Origin::signed(BOB)));
-		// 2 is manufacture
-		assert_ok!(TemplateModule::approve_vm(Origin::signed(ALICE), BOB));
-		// 4 claimed to be distributer
-		assert_ok!(TemplateModule::claim_vad(Origin::signed(DAVE)));
-		// 4 is distributer
-		assert_ok!(TemplateModule::approve_vad(Origin::signed(ALICE), DAVE));
-		// 7 claimed to be distributer
-		assert_ok!(TemplateModule::claim_vad(Origin::signed(EVE)));
-		// 7 is distributer
-		assert_ok!(TemplateModule::approve_vad(Origin::signed(ALICE), EVE));
+		setup_accounts();
 		//register vaciine type info
-		assert_ok!(TemplateModule::register_vac_type(Origin::signed(ALICE)));
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
 		//register vaciine info
-		assert_ok!(TemplateModule::register_vac_info(Origin::signed(BOB), Some(1)));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
 
 		// Not manufacture or distributer transfer vaccine
 		assert_noop!(
-			TemplateModule::transfer_vaccine(Origin::signed(FRANK), DAVE, Some(1)),
-			Error::<Test>::NotManufacture
+			Vaccine::transfer_vaccine(
+				Origin::signed(FRANK),
+				role_id(FRANK),
+				role_id(DAVE),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::InvalidRole
 		);
 
 		// transfer to myself
 		assert_noop!(
-			TemplateModule::transfer_vaccine(Origin::signed(BOB), BOB, Some(1)),
+			Vaccine::transfer_vaccine(Origin::signed(BOB), role_id(BOB), role_id(BOB), vac_id(1)),
 			Error::<Test>::TransferByMyself
 		);
 
 		// Not owner transfer vaccine
 		assert_noop!(
-			TemplateModule::transfer_vaccine(Origin::signed(DAVE), FRANK, Some(1)),
+			Vaccine::transfer_vaccine(Origin::signed(DAVE), role_id(DAVE), role_id(EVE), vac_id(1)),
 			Error::<Test>::WrongVaccineOwner
 		);
 
 		// transfer not exist vaccine
 		assert_noop!(
-			TemplateModule::transfer_vaccine(Origin::signed(BOB), DAVE, Some(3)),
+			Vaccine::transfer_vaccine(Origin::signed(BOB), role_id(BOB), role_id(DAVE), vac_id(3)),
 			Error::<Test>::NotRegisteredVaccine
 		);
 
 		// transfer vaccine
-		assert_ok!(TemplateModule::transfer_vaccine(Origin::signed(BOB), DAVE, Some(1)));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
 
 		// receive not exist vaccine
 		assert_noop!(
-			TemplateModule::receive_vaccine(Origin::signed(DAVE), BOB, Some(3)),
+			Vaccine::receive_vaccine(Origin::signed(DAVE), role_id(DAVE), role_id(BOB), vac_id(3)),
 			Error::<Test>::NotRegisteredVaccine
 		);
 
 		// not manufacture or distributer receive vaccine
 		assert_noop!(
-			TemplateModule::receive_vaccine(Origin::signed(FRANK), BOB, Some(1)),
-			Error::<Test>::NotManufacture
+			Vaccine::receive_vaccine(
+				Origin::signed(FRANK),
+				role_id(FRANK),
+				role_id(BOB),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::InvalidRole
 		);
 
 		// not specified receiver
 		assert_noop!(
-			TemplateModule::receive_vaccine(Origin::signed(EVE), FRANK, Some(1)),
+			Vaccine::receive_vaccine(Origin::signed(EVE), role_id(EVE), role_id(BOB), vac_id(1)),
 			Error::<Test>::NotVaccineBuyer
 		);
 
 		// receive from not vaccine owner
 		assert_noop!(
-			TemplateModule::receive_vaccine(Origin::signed(DAVE), EVE, Some(1)),
+			Vaccine::receive_vaccine(Origin::signed(DAVE), role_id(DAVE), role_id(EVE), vac_id(1)),
 			Error::<Test>::WrongVaccineOwner
 		);
 
 		// receive vaccine
-		assert_ok!(TemplateModule::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
 
 		// receive twice
 		assert_noop!(
-			TemplateModule::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)),
+			Vaccine::receive_vaccine(Origin::signed(DAVE), role_id(DAVE), role_id(BOB), vac_id(1)),
 			Error::<Test>::VaccineAlreadyMine
 		);
 	});
@@ -253,7 +313,6 @@ fn should_cause_error_for_transfer_vaccine() {
 
 // ALICE(1): system manager
 // BOB(2): manufacture
-// CHARLIE(3): VAO
 // DAVE(4): VAD
 // EVE(5): VAD
 // FRANK(6): user
@@ -261,94 +320,1624 @@ fn should_cause_error_for_transfer_vaccine() {
 #[test]
 fn should_cause_error_for_get_confirm_vaccine() {
 	ExtBuilder::default().set_genesis_account().execute_with(|| {
-		// 1 is system manager by default
-		assert_eq!(TemplateModule::sys_man(ALICE), true);
-		// 2 claimed to be manufacture
-		assert_ok!(TemplateModule::claim_vm(Origin::signed(BOB)));
-		// 2 is manufacture
-		assert_ok!(TemplateModule::approve_vm(Origin::signed(ALICE), BOB));
-		// 4 claimed to be distributer
-		assert_ok!(TemplateModule::claim_vad(Origin::signed(DAVE)));
-		// 4 is distributer
-		assert_ok!(TemplateModule::approve_vad(Origin::signed(ALICE), DAVE));
-		// 7 claimed to be distributer
-		assert_ok!(TemplateModule::claim_vad(Origin::signed(EVE)));
-		// 7 is distributer
-		assert_ok!(TemplateModule::approve_vad(Origin::signed(ALICE), EVE));
-		//register vaciine type info
-		assert_ok!(TemplateModule::register_vac_type(Origin::signed(ALICE)));
-		//register vaciine info
-		assert_ok!(TemplateModule::register_vac_info(Origin::signed(BOB), Some(1)));
+		setup_accounts();
 		//register vaciine type info
-		assert_ok!(TemplateModule::register_vac_type(Origin::signed(ALICE)));
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
 		//register vaciine info
-		assert_ok!(TemplateModule::register_vac_info(Origin::signed(BOB), Some(2)));
-		// transfer vaccine
-		assert_ok!(TemplateModule::transfer_vaccine(Origin::signed(BOB), DAVE, Some(1)));
-		// receive vaccine
-		assert_ok!(TemplateModule::receive_vaccine(Origin::signed(DAVE), BOB, Some(1)));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
 		// transfer vaccine
-		assert_ok!(TemplateModule::transfer_vaccine(Origin::signed(BOB), DAVE, Some(2)));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
 		// receive vaccine
-		assert_ok!(TemplateModule::receive_vaccine(Origin::signed(DAVE), BOB, Some(2)));
-		// transfer vaccine
-		assert_ok!(TemplateModule::transfer_vaccine(Origin::signed(DAVE), FRANK, Some(2)));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
 
-		// only manufacture or distributer
+		// only distributer
 		assert_noop!(
-			TemplateModule::transfer_get_vaccine_right(Origin::signed(FRANK), DAVE, Some(1)),
-			Error::<Test>::NotManufacture
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(FRANK),
+				role_id(FRANK),
+				role_id(GEORGE),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::InvalidRole
 		);
-		// only manufacture or distributer
+		// not exist vaccine
 		assert_noop!(
-			TemplateModule::transfer_get_vaccine_right(Origin::signed(DAVE), FRANK, Some(3)),
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(FRANK),
+				vac_id(3)
+			),
 			Error::<Test>::NotRegisteredVaccine
 		);
 		// transfer to myself
 		assert_noop!(
-			TemplateModule::transfer_get_vaccine_right(Origin::signed(DAVE), DAVE, Some(1)),
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(DAVE),
+				vac_id(1)
+			),
 			Error::<Test>::TransferByMyself
 		);
 		// not vaccine owner
 		assert_noop!(
-			TemplateModule::transfer_get_vaccine_right(Origin::signed(EVE), FRANK, Some(1)),
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(EVE),
+				role_id(EVE),
+				role_id(FRANK),
+				vac_id(1)
+			),
 			Error::<Test>::WrongVaccineOwner
 		);
 		// receive vaccine inoculation right
-		assert_ok!(TemplateModule::transfer_get_vaccine_right(
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
 			Origin::signed(DAVE),
-			FRANK,
-			Some(1)
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(1)
 		));
 
-		// not send final transfer to me
+		// not exist vaccine
 		assert_noop!(
-			TemplateModule::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(3)),
+			Vaccine::confirm_vaccine(
+				Origin::signed(FRANK),
+				role_id(FRANK),
+				role_id(DAVE),
+				vac_id(3)
+			),
 			Error::<Test>::NotRegisteredVaccine
 		);
 		// not vaccine user
 		assert_noop!(
-			TemplateModule::confirm_vaccine(Origin::signed(GEORGE), Some(DAVE), Some(1)),
+			Vaccine::confirm_vaccine(
+				Origin::signed(GEORGE),
+				role_id(GEORGE),
+				role_id(DAVE),
+				vac_id(1)
+			),
 			Error::<Test>::NotVaccineBuyer
 		);
 		// not vaccine previous owner
 		assert_noop!(
-			TemplateModule::confirm_vaccine(Origin::signed(FRANK), Some(EVE), Some(1)),
+			Vaccine::confirm_vaccine(
+				Origin::signed(FRANK),
+				role_id(FRANK),
+				role_id(EVE),
+				vac_id(1)
+			),
 			Error::<Test>::WrongVaccineOwner
 		);
-		// not transfer vaccine but transfer_get_vaccine_right
+
+		// confirm use vaccine
+		assert_ok!(Vaccine::confirm_vaccine(
+			Origin::signed(FRANK),
+			role_id(FRANK),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_eq!(Vaccine::vaccine_passports(role_id(FRANK)).unwrap().vac_list.len(), 1);
+	});
+}
+
+// ALICE(1): system manager
+// BOB(2): manufacture
+// DAVE(4): VAD
+// EVE(5): VAD
+// FRANK(6): user
+// GEORGE(7): user
+#[test]
+fn should_reject_impersonation_of_role_id() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+
+		// VAD signs as the system manager
 		assert_noop!(
-			TemplateModule::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(2)),
-			Error::<Test>::NotSendFinalTransfer
+			Vaccine::register_vac_type(
+				Origin::signed(DAVE),
+				role_id(ALICE),
+				VacType::COVID19,
+				DOSES
+			),
+			pallet_account::Error::<Test>::NotRoleOwner
 		);
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
 
-		// confirm use vaccine
-		assert_ok!(TemplateModule::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(1)));
+		// VAD signs as the manufacture
+		assert_noop!(
+			Vaccine::register_vac_info(
+				Origin::signed(DAVE),
+				role_id(BOB),
+				vac_id(1),
+				VacType::COVID19,
+				EXPIRES_AT
+			),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
 
-		// not send final transfer to me
+		// VAD moves the manufacture's vaccine
 		assert_noop!(
-			TemplateModule::confirm_vaccine(Origin::signed(FRANK), Some(DAVE), Some(1)),
-			Error::<Test>::VaccineAlreadyMine
+			Vaccine::transfer_vaccine(Origin::signed(EVE), role_id(BOB), role_id(EVE), vac_id(1)),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+
+		// VAD receives on behalf of another VAD
+		assert_noop!(
+			Vaccine::receive_vaccine(Origin::signed(EVE), role_id(DAVE), role_id(BOB), vac_id(1)),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+
+		// user approves as the authorized organization
+		assert_noop!(
+			Vaccine::approve_vaccine(Origin::signed(FRANK), role_id(CHARLIE), vac_id(1)),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+
+		// VAD administers from another VAD's stock
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(EVE),
+				role_id(DAVE),
+				role_id(FRANK),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+
+		// another user confirms the inoculation
+		assert_noop!(
+			Vaccine::confirm_vaccine(
+				Origin::signed(GEORGE),
+				role_id(FRANK),
+				role_id(DAVE),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+	});
+}
+
+// ALICE(1): system manager
+// BOB(2): manufacture
+// DAVE(4): VAD
+// EVE(5): VAD
+// FRANK(6): user
+#[test]
+fn should_cut_off_revoked_or_suspended_distributer() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+
+		// suspended distributer can not administer
+		assert_ok!(Account::suspend_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			1_000,
+			1
+		));
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(FRANK),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::AccountSuspended
+		);
+
+		// revoked distributer can neither administer nor move stock
+		assert_ok!(Account::revoke_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			2
+		));
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(FRANK),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::AlreadyRevoked
+		);
+		assert_noop!(
+			Vaccine::transfer_vaccine(Origin::signed(DAVE), role_id(DAVE), role_id(EVE), vac_id(1)),
+			pallet_account::Error::<Test>::AlreadyRevoked
+		);
+	});
+}
+
+// DAVE(4) runs the warehouse 8 and the clinic 9
+#[test]
+fn should_attribute_sub_account_actions_to_organisation() {
+	use pallet_account::{Capability, SubAccountKind};
+
+	const WAREHOUSE: u64 = 8;
+	const CLINIC: u64 = 9;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		assert_ok!(Account::create_sub_account(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(WAREHOUSE),
+			WAREHOUSE,
+			SubAccountKind::Warehouse,
+			vec![Capability::ReceiveVaccine, Capability::TransferVaccine]
+		));
+		assert_ok!(Account::create_sub_account(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(CLINIC),
+			CLINIC,
+			SubAccountKind::Clinic,
+			vec![Capability::ReceiveVaccine, Capability::AdministerVaccine]
+		));
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+
+		// the warehouse receives stock on behalf of DAVE
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(WAREHOUSE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(WAREHOUSE),
+			role_id(WAREHOUSE),
+			role_id(BOB),
+			vac_id(1)
+		));
+		let tracking = Vaccine::ownership_tracking(vac_id(1)).pop().unwrap();
+		assert_eq!(tracking.to, Some(role_id(WAREHOUSE)));
+		assert_eq!(tracking.to_org, Some(role_id(DAVE)));
+		assert_eq!(tracking.from_org, None);
+
+		// a warehouse does not administer vaccines
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(WAREHOUSE),
+				role_id(WAREHOUSE),
+				role_id(FRANK),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::PermissionDeny
+		);
+
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(WAREHOUSE),
+			role_id(WAREHOUSE),
+			role_id(CLINIC),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(CLINIC),
+			role_id(CLINIC),
+			role_id(WAREHOUSE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(CLINIC),
+			role_id(CLINIC),
+			role_id(FRANK),
+			vac_id(1)
+		));
+		let tracking = Vaccine::ownership_tracking(vac_id(1)).pop().unwrap();
+		assert_eq!(tracking.from, Some(role_id(CLINIC)));
+		assert_eq!(tracking.from_org, Some(role_id(DAVE)));
+
+		// the clinic is not removed with the vial it holds, the warehouse is
+		assert_noop!(
+			Account::remove_sub_account(Origin::signed(DAVE), role_id(DAVE), role_id(CLINIC)),
+			Error::<Test>::SubAccountHoldsVials
+		);
+		assert_ok!(Account::remove_sub_account(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(WAREHOUSE)
+		));
+
+		// the clinic is cut off together with DAVE
+		assert_ok!(Account::revoke_role(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			role_id(DAVE),
+			Role::VAD,
+			1
+		));
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(CLINIC),
+				role_id(CLINIC),
+				role_id(GEORGE),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::AlreadyRevoked
+		);
+	});
+}
+
+#[test]
+fn should_keep_vaccines_after_key_rotation() {
+	const NEW_KEY: u64 = 8;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+
+		assert_ok!(Account::rotate_key(Origin::signed(BOB), role_id(BOB), NEW_KEY));
+
+		// the old key lost control over the stock of the role ID
+		assert_noop!(
+			Vaccine::transfer_vaccine(Origin::signed(BOB), role_id(BOB), role_id(DAVE), vac_id(1)),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(NEW_KEY),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().owner_id, Some(role_id(BOB)));
+	});
+}
+
+#[test]
+fn should_follow_permission_table() {
+	use pallet_account::Capability;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+
+		// distributers only keep the stock handling
+		assert_ok!(Account::set_role_permissions(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			Role::VAD,
+			vec![Capability::TransferVaccine, Capability::ReceiveVaccine]
+		));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(FRANK),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::InvalidRole
+		);
+
+		// authorized organizations may administer instead
+		assert_ok!(Account::set_role_permissions(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			Role::VAO,
+			vec![
+				Capability::ApproveVaccine,
+				Capability::AdministerVaccine,
+				Capability::ReceiveVaccine
+			]
+		));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(CHARLIE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(CHARLIE),
+			role_id(CHARLIE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+	});
+}
+
+#[test]
+fn should_record_operator_key_signing_for_distributer() {
+	use pallet_account::Capability;
+	const TABLET: u64 = 20;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		setup_accounts();
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+		assert_eq!(Vaccine::ownership_tracking(vac_id(1)).pop().unwrap().delegation, None);
+
+		// the nurse's tablet administers doses for DAVE during a shift
+		assert_ok!(Account::delegate(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			TABLET,
+			vec![Capability::AdministerVaccine],
+			10_000
+		));
+		assert_noop!(
+			Vaccine::transfer_vaccine(
+				Origin::signed(TABLET),
+				role_id(DAVE),
+				role_id(CHARLIE),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::PermissionDeny
+		);
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(TABLET),
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+		let tracking = Vaccine::ownership_tracking(vac_id(1)).pop().unwrap();
+		assert_eq!(tracking.from, Some(role_id(DAVE)));
+		assert_eq!(tracking.to, Some(role_id(FRANK)));
+		assert_eq!(tracking.delegation, Some((role_id(DAVE), TABLET)));
+
+		// an unknown key is not the owner either
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(TABLET + 1),
+				role_id(DAVE),
+				role_id(GEORGE),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::NotRoleOwner
+		);
+
+		// the shift is over
+		Timestamp::set_timestamp(11_000);
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(TABLET),
+				role_id(DAVE),
+				role_id(GEORGE),
+				vac_id(1)
+			),
+			pallet_account::Error::<Test>::DelegationExpired
+		);
+	});
+}
+
+#[test]
+fn should_erase_passport_and_keep_supply_chain_history() {
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::confirm_vaccine(
+			Origin::signed(FRANK),
+			role_id(FRANK),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		let history = Vaccine::ownership_tracking(vac_id(1));
+
+		assert_ok!(Account::request_erasure(Origin::signed(FRANK), role_id(FRANK)));
+		assert_ok!(Account::confirm_erasure(Origin::signed(ALICE), role_id(ALICE), role_id(FRANK)));
+
+		assert!(Vaccine::vaccine_passports(role_id(FRANK)).is_none());
+		assert!(Account::accounts(role_id(FRANK)).is_none());
+		assert!(Account::is_erased(&role_id(FRANK)));
+		assert_eq!(Vaccine::ownership_tracking(vac_id(1)), history);
+		assert!(Vaccine::used_vaccine(vac_id(1), role_id(FRANK)));
+		System::assert_last_event(Event::Account(pallet_account::Event::AccountErased(
+			BlakeTwo256::hash_of(&role_id(FRANK)),
+			role_id(ALICE),
+		)));
+
+		// the erased role ID can not be given to somebody else
+		assert_noop!(
+			Account::register_account(Origin::root(), role_id(FRANK), 20, Role::USER),
+			pallet_account::Error::<Test>::AlreadyErased
 		);
 	});
 }
+
+#[test]
+fn should_reject_vaccine_calls_beyond_quota() {
+	use crate::{CheckVaccineQuota, QuotaUsage, QUOTA_EXHAUSTED};
+	use frame_support::weights::DispatchInfo;
+	use sp_runtime::{
+		traits::SignedExtension, transaction_validity::InvalidTransaction, DispatchError,
+	};
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		let info = DispatchInfo::default();
+		let register = Call::Vaccine(crate::Call::register_vac_info {
+			manufacture: role_id(BOB),
+			vac_id: vac_id(1),
+			vac_type: VacType::COVID19,
+			expires_at: EXPIRES_AT,
+		});
+
+		assert_noop!(
+			Vaccine::set_role_quota(Origin::signed(ALICE), Role::VM, Some(2)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Vaccine::set_role_quota(Origin::root(), Role::VM, Some(2)));
+		assert_eq!(Vaccine::quota_of(&role_id(BOB)), 2);
+		assert_eq!(Vaccine::quota_of(&role_id(CHARLIE)), 100);
+
+		// calls naming BOB but signed by another key fail on dispatch, they do not use up his
+		// quota
+		for _ in 0..3 {
+			assert_ok!(CheckVaccineQuota::<Test>::new().pre_dispatch(&DAVE, &register, &info, 0));
+		}
+		assert_eq!(Vaccine::quota_usage(&role_id(BOB)).used, 0);
+
+		// included calls are counted, once the quota is used up the pool rejects them
+		assert_ok!(CheckVaccineQuota::<Test>::new().pre_dispatch(&BOB, &register, &info, 0));
+		assert_ok!(CheckVaccineQuota::<Test>::new().pre_dispatch(&BOB, &register, &info, 0));
+		assert_eq!(
+			Vaccine::quota_usage(&role_id(BOB)),
+			QuotaUsage { used: 2, quota: 2, resets_at: 10 }
+		);
+		assert_eq!(
+			CheckVaccineQuota::<Test>::new().validate(&BOB, &register, &info, 0),
+			Err(InvalidTransaction::Custom(QUOTA_EXHAUSTED).into())
+		);
+
+		// other role IDs and calls of other pallets are not affected
+		let approve = Call::Vaccine(crate::Call::approve_vaccine {
+			organization: role_id(CHARLIE),
+			vac_id: vac_id(1),
+		});
+		assert_ok!(CheckVaccineQuota::<Test>::new().validate(&CHARLIE, &approve, &info, 0));
+		let remark = Call::System(frame_system::Call::remark { remark: vec![] });
+		assert_ok!(CheckVaccineQuota::<Test>::new().validate(&BOB, &remark, &info, 0));
+
+		// the quota of a role ID overrides the one of its roles
+		assert_ok!(Vaccine::set_role_id_quota(Origin::root(), role_id(BOB), Some(3)));
+		assert_ok!(CheckVaccineQuota::<Test>::new().validate(&BOB, &register, &info, 0));
+
+		// the next window starts afresh
+		System::set_block_number(10);
+		assert_eq!(
+			Vaccine::quota_usage(&role_id(BOB)),
+			QuotaUsage { used: 0, quota: 3, resets_at: 20 }
+		);
+	});
+}
+
+#[test]
+fn should_register_lots_of_vials() {
+	use crate::{LotInfo, VacStatus};
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		Timestamp::set_timestamp(2_000);
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		let lot = b"LOT42".to_vec();
+
+		// only manufactures register lots of a registered type and a bounded size
+		assert_noop!(
+			Vaccine::register_lot(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				lot.clone(),
+				1_000,
+				3,
+				VacType::COVID19,
+				EXPIRES_AT
+			),
+			pallet_account::Error::<Test>::InvalidRole
+		);
+		assert_noop!(
+			Vaccine::register_lot(
+				Origin::signed(BOB),
+				role_id(BOB),
+				lot.clone(),
+				1_000,
+				3,
+				VacType::FLU,
+				EXPIRES_AT
+			),
+			Error::<Test>::ManuCanNotCreateVaccine
+		);
+		for (number, quantity) in [(lot.clone(), 0), (lot.clone(), 11), (vec![], 3)] {
+			assert_noop!(
+				Vaccine::register_lot(
+					Origin::signed(BOB),
+					role_id(BOB),
+					number,
+					1_000,
+					quantity,
+					VacType::COVID19,
+					EXPIRES_AT
+				),
+				Error::<Test>::InvalidLot
+			);
+		}
+		// a vial ID of the lot is taken
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			b"LOT7-2".to_vec(),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+		assert_noop!(
+			Vaccine::register_lot(
+				Origin::signed(BOB),
+				role_id(BOB),
+				b"LOT7".to_vec(),
+				1_000,
+				3,
+				VacType::COVID19,
+				EXPIRES_AT
+			),
+			Error::<Test>::VaccineIsRegistered
+		);
+
+		assert_ok!(Vaccine::register_lot(
+			Origin::signed(BOB),
+			role_id(BOB),
+			lot.clone(),
+			1_000,
+			10,
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+		System::assert_last_event(Event::Vaccine(crate::Event::LotRegistered(
+			lot.clone(),
+			role_id(BOB),
+			10,
+		)));
+		assert_eq!(
+			Vaccine::lots(&lot),
+			Some(LotInfo {
+				manufacture_id: role_id(BOB),
+				vac_type: VacType::COVID19,
+				manufactured_at: 1_000,
+				quantity: 10,
+			})
+		);
+		assert_noop!(
+			Vaccine::register_lot(
+				Origin::signed(BOB),
+				role_id(BOB),
+				lot.clone(),
+				1_000,
+				1,
+				VacType::COVID19,
+				EXPIRES_AT
+			),
+			Error::<Test>::LotIsRegistered
+		);
+
+		// vials are numbered from one and link back to their lot
+		let vials = Vaccine::vials_of_lot(&lot);
+		assert_eq!(vials.len(), 10);
+		assert_eq!(vials[0], b"LOT42-1".to_vec());
+		assert_eq!(vials[9], b"LOT42-10".to_vec());
+		for vac_id in &vials {
+			let vac_info = Vaccine::vaccines(vac_id).unwrap();
+			assert_eq!(vac_info.lot, Some(lot.clone()));
+			assert_eq!(vac_info.owner_id, Some(role_id(BOB)));
+			let tracking = Vaccine::ownership_tracking(vac_id);
+			assert_eq!(tracking.len(), 1);
+			assert_eq!(tracking[0].status, Some(VacStatus::Manufactured));
+			assert_eq!(tracking[0].time, Some(2_000));
+		}
+		assert_eq!(Vaccine::vaccines(b"LOT7-2".to_vec()).unwrap().lot, None);
+		assert!(Vaccine::vials_of_lot(b"LOT7").is_empty());
+
+		// a vial of a lot moves along the supply chain like any other
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vials[4].clone()
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vials[4].clone()
+		));
+		assert_eq!(Vaccine::vaccines(&vials[4]).unwrap().owner_id, Some(role_id(DAVE)));
+	});
+}
+
+#[test]
+fn should_reject_expired_vials() {
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+
+		// the expiry lies in the future at registration
+		assert_noop!(
+			Vaccine::register_vac_info(
+				Origin::signed(BOB),
+				role_id(BOB),
+				vac_id(1),
+				VacType::COVID19,
+				1_000
+			),
+			Error::<Test>::VaccineExpired
+		);
+		assert_noop!(
+			Vaccine::register_lot(
+				Origin::signed(BOB),
+				role_id(BOB),
+				b"L".to_vec(),
+				500,
+				1,
+				VacType::COVID19,
+				900
+			),
+			Error::<Test>::VaccineExpired
+		);
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			5_000
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(2),
+			VacType::COVID19,
+			20_000
+		));
+		assert_ok!(Vaccine::register_lot(
+			Origin::signed(BOB),
+			role_id(BOB),
+			b"L".to_vec(),
+			500,
+			1,
+			VacType::COVID19,
+			8_000
+		));
+		let lot_vial = b"L-1".to_vec();
+		assert_eq!(Vaccine::vaccines(&lot_vial).unwrap().expires_at, Some(8_000));
+
+		// holders list the vials expiring soon, the soonest first
+		assert_eq!(Vaccine::expiring_vials(&role_id(BOB), 5_000), vec![(vac_id(1), 5_000)]);
+		assert_eq!(
+			Vaccine::expiring_vials(&role_id(BOB), 100_000),
+			vec![(vac_id(1), 5_000), (lot_vial.clone(), 8_000), (vac_id(2), 20_000)]
+		);
+
+		// the listing follows the owner
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(2)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(2)
+		));
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			lot_vial.clone()
+		));
+		assert_eq!(Vaccine::expiring_vials(&role_id(DAVE), 100_000), vec![(vac_id(2), 20_000)]);
+		assert_eq!(Vaccine::held_vials(role_id(BOB), vac_id(2)), None);
+
+		// expired vials are neither moved nor administered, but still listed
+		Timestamp::set_timestamp(8_000);
+		assert_noop!(
+			Vaccine::transfer_vaccine(Origin::signed(BOB), role_id(BOB), role_id(EVE), vac_id(1)),
+			Error::<Test>::VaccineExpired
+		);
+		assert_noop!(
+			Vaccine::receive_vaccine(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(BOB),
+				lot_vial.clone()
+			),
+			Error::<Test>::VaccineExpired
+		);
+		assert_eq!(
+			Vaccine::expiring_vials(&role_id(BOB), 0),
+			vec![(vac_id(1), 5_000), (lot_vial, 8_000)]
+		);
+
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(2)
+		));
+		Timestamp::set_timestamp(20_000);
+		assert_noop!(
+			Vaccine::confirm_vaccine(
+				Origin::signed(FRANK),
+				role_id(FRANK),
+				role_id(DAVE),
+				vac_id(2)
+			),
+			Error::<Test>::VaccineExpired
+		);
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(GEORGE),
+				vac_id(2)
+			),
+			Error::<Test>::VaccineExpired
+		);
+	});
+}
+
+#[test]
+fn should_follow_vial_status_transitions() {
+	use crate::VacStatus;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Manufactured);
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Shipped);
+
+		// a quarantined vial is held back until released to where it was
+		assert_noop!(
+			Vaccine::quarantine_vaccine(Origin::signed(DAVE), role_id(DAVE), vac_id(1)),
+			Error::<Test>::WrongVaccineOwner
+		);
+		assert_ok!(Vaccine::quarantine_vaccine(Origin::signed(BOB), role_id(BOB), vac_id(1)));
+		System::assert_last_event(Event::Vaccine(crate::Event::VaccineStatusChanged(
+			vac_id(1),
+			VacStatus::Quarantined,
+		)));
+		assert_noop!(
+			Vaccine::receive_vaccine(Origin::signed(DAVE), role_id(DAVE), role_id(BOB), vac_id(1)),
+			Error::<Test>::VaccineQuarantined
+		);
+		assert_noop!(
+			Vaccine::quarantine_vaccine(Origin::signed(BOB), role_id(BOB), vac_id(1)),
+			Error::<Test>::VaccineQuarantined
+		);
+		assert_ok!(Vaccine::release_vaccine(Origin::signed(BOB), role_id(BOB), vac_id(1)));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Shipped);
+		assert_noop!(
+			Vaccine::release_vaccine(Origin::signed(BOB), role_id(BOB), vac_id(1)),
+			Error::<Test>::VaccineNotQuarantined
+		);
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Received);
+
+		// a vial on its way to another distributer is not administered
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(EVE),
+			vac_id(1)
+		));
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(FRANK),
+				vac_id(1)
+			),
+			Error::<Test>::InvalidStatusTransition
+		);
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(EVE),
+			role_id(EVE),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(EVE),
+			role_id(EVE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Usable);
+
+		// a destroyed vial leaves the stock of its holder for good
+		assert_ok!(Vaccine::destroy_vaccine(Origin::signed(EVE), role_id(EVE), vac_id(1)));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Destroyed);
+		assert_eq!(Vaccine::held_vials(role_id(EVE), vac_id(1)), None);
+		let tracking = Vaccine::ownership_tracking(vac_id(1)).pop().unwrap();
+		assert_eq!(tracking.from, Some(role_id(EVE)));
+		assert_eq!(tracking.status, Some(VacStatus::Destroyed));
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(EVE),
+				role_id(EVE),
+				role_id(GEORGE),
+				vac_id(1)
+			),
+			Error::<Test>::VaccineDestroyed
+		);
+		assert_noop!(
+			Vaccine::destroy_vaccine(Origin::signed(EVE), role_id(EVE), vac_id(1)),
+			Error::<Test>::VaccineDestroyed
+		);
+
+		// anyone marks a vial past its expiry date as expired, its holder disposes of it
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(2),
+			VacType::COVID19,
+			5_000
+		));
+		assert_noop!(
+			Vaccine::expire_vaccine(Origin::signed(FRANK), vac_id(2)),
+			Error::<Test>::VaccineNotExpired
+		);
+		Timestamp::set_timestamp(5_000);
+		assert_ok!(Vaccine::expire_vaccine(Origin::signed(FRANK), vac_id(2)));
+		System::assert_last_event(Event::Vaccine(crate::Event::VaccineStatusChanged(
+			vac_id(2),
+			VacStatus::Expired,
+		)));
+		assert_noop!(
+			Vaccine::expire_vaccine(Origin::signed(FRANK), vac_id(2)),
+			Error::<Test>::VaccineExpired
+		);
+		assert_noop!(
+			Vaccine::quarantine_vaccine(Origin::signed(BOB), role_id(BOB), vac_id(2)),
+			Error::<Test>::VaccineExpired
+		);
+		assert_eq!(Vaccine::expiring_vials(&role_id(BOB), 0), vec![(vac_id(2), 5_000)]);
+		assert_ok!(Vaccine::destroy_vaccine(Origin::signed(BOB), role_id(BOB), vac_id(2)));
+		assert!(Vaccine::expiring_vials(&role_id(BOB), 0).is_empty());
+	});
+}
+
+#[test]
+fn should_recall_vials_and_notify_their_users() {
+	use crate::{RecallTarget, VacStatus};
+	const HENRY: u64 = 8;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		register(HENRY, Role::VM);
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::COVID19,
+			DOSES
+		));
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+		let lot = b"LOT1".to_vec();
+		assert_ok!(Vaccine::register_lot(
+			Origin::signed(BOB),
+			role_id(BOB),
+			lot.clone(),
+			1_000,
+			3,
+			VacType::COVID19,
+			EXPIRES_AT
+		));
+
+		// FRANK had his dose, GEORGE was handed one
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::confirm_vaccine(
+			Origin::signed(FRANK),
+			role_id(FRANK),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(GEORGE),
+			vac_id(1)
+		));
+
+		// only system managers and the manufacture of the vial
+		assert_noop!(
+			Vaccine::recall(
+				Origin::signed(CHARLIE),
+				role_id(CHARLIE),
+				RecallTarget::Vial(vac_id(1))
+			),
+			pallet_account::Error::<Test>::InvalidRole
+		);
+		assert_noop!(
+			Vaccine::recall(Origin::signed(HENRY), role_id(HENRY), RecallTarget::Vial(vac_id(1))),
+			Error::<Test>::NotManufacture
+		);
+		assert_ok!(Vaccine::recall(
+			Origin::signed(BOB),
+			role_id(BOB),
+			RecallTarget::Vial(vac_id(1))
+		));
+		System::assert_last_event(Event::Vaccine(crate::Event::Recalled(
+			RecallTarget::Vial(vac_id(1)),
+			role_id(BOB),
+			1,
+		)));
+		for user in [FRANK, GEORGE] {
+			let notice = Event::Vaccine(crate::Event::RecallNotice(vac_id(1), role_id(user)));
+			assert!(System::events().iter().any(|record| record.event == notice));
+		}
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Recalled);
+		assert_eq!(Vaccine::held_vials(role_id(DAVE), vac_id(1)), None);
+		let passport = Vaccine::vaccine_passports(role_id(FRANK)).unwrap();
+		assert_eq!(passport.recalled.into_inner(), vec![vac_id(1)]);
+
+		// a dose handed out before the recall is confirmed, and flagged at once
+		assert_ok!(Vaccine::confirm_vaccine(
+			Origin::signed(GEORGE),
+			role_id(GEORGE),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		let passport = Vaccine::vaccine_passports(role_id(GEORGE)).unwrap();
+		assert_eq!(passport.vac_list.into_inner(), vec![vac_id(1)]);
+		assert_eq!(passport.recalled.into_inner(), vec![vac_id(1)]);
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Recalled);
+
+		// the vial is out of circulation
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(EVE),
+				vac_id(1)
+			),
+			Error::<Test>::VaccineRecalled
+		);
+		assert_noop!(
+			Vaccine::recall(Origin::signed(BOB), role_id(BOB), RecallTarget::Vial(vac_id(1))),
+			Error::<Test>::VaccineRecalled
+		);
+		assert_ok!(Vaccine::destroy_vaccine(Origin::signed(DAVE), role_id(DAVE), vac_id(1)));
+
+		// a lot recall passes over the vials recalled before
+		let vials = Vaccine::vials_of_lot(&lot);
+		assert_ok!(Vaccine::recall(
+			Origin::signed(BOB),
+			role_id(BOB),
+			RecallTarget::Vial(vials[1].clone())
+		));
+		assert_noop!(
+			Vaccine::recall(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				RecallTarget::Lot(b"LOT2".to_vec())
+			),
+			Error::<Test>::InvalidLot
+		);
+		assert_ok!(Vaccine::recall(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			RecallTarget::Lot(lot.clone())
+		));
+		System::assert_last_event(Event::Vaccine(crate::Event::Recalled(
+			RecallTarget::Lot(lot.clone()),
+			role_id(ALICE),
+			1,
+		)));
+		assert_eq!(Vaccine::vaccines(&vials[0]).unwrap().status, VacStatus::Recalled);
+
+		// the vials of the next batch are out of circulation already
+		assert_eq!(Vaccine::vaccines(&vials[2]).unwrap().status, VacStatus::Manufactured);
+		assert_noop!(
+			Vaccine::transfer_vaccine(
+				Origin::signed(BOB),
+				role_id(BOB),
+				role_id(DAVE),
+				vials[2].clone()
+			),
+			Error::<Test>::VaccineRecalled
+		);
+		assert_ok!(Vaccine::recall(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			RecallTarget::Lot(lot.clone())
+		));
+		for vac_id in &vials {
+			assert_eq!(Vaccine::vaccines(vac_id).unwrap().status, VacStatus::Recalled);
+		}
+		assert_eq!(Vaccine::lot_recalls(&lot), Some(3));
+		assert_noop!(
+			Vaccine::recall(Origin::signed(ALICE), role_id(ALICE), RecallTarget::Lot(lot)),
+			Error::<Test>::VaccineRecalled
+		);
+	});
+}
+
+#[test]
+fn should_fill_vials_with_the_doses_of_their_type() {
+	use crate::VacStatus;
+
+	ExtBuilder::default().set_genesis_account().execute_with(|| {
+		setup_accounts();
+		assert_noop!(
+			Vaccine::register_vac_type(Origin::signed(ALICE), role_id(ALICE), VacType::HPV, 0),
+			Error::<Test>::InvalidDoses
+		);
+		assert_noop!(
+			Vaccine::register_vac_type(Origin::signed(ALICE), role_id(ALICE), VacType::FLU, 11),
+			Error::<Test>::InvalidDoses
+		);
+		// single-dose HPV and 10-dose flu vials
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::HPV,
+			1
+		));
+		assert_ok!(Vaccine::register_vac_type(
+			Origin::signed(ALICE),
+			role_id(ALICE),
+			VacType::FLU,
+			10
+		));
+		assert_eq!(Vaccine::doses_per_vial(VacType::HPV), Some(1));
+
+		assert_ok!(Vaccine::register_vac_info(
+			Origin::signed(BOB),
+			role_id(BOB),
+			vac_id(1),
+			VacType::HPV,
+			EXPIRES_AT
+		));
+		assert_ok!(Vaccine::register_lot(
+			Origin::signed(BOB),
+			role_id(BOB),
+			b"FLU1".to_vec(),
+			1_000,
+			2,
+			VacType::FLU,
+			EXPIRES_AT
+		));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().max_inoculations_number, 1);
+		for vac_id in Vaccine::vials_of_lot(b"FLU1") {
+			assert_eq!(Vaccine::vaccines(vac_id).unwrap().max_inoculations_number, 10);
+		}
+
+		// the only dose of the HPV vial uses it up
+		assert_ok!(Vaccine::transfer_vaccine(
+			Origin::signed(BOB),
+			role_id(BOB),
+			role_id(DAVE),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::receive_vaccine(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(BOB),
+			vac_id(1)
+		));
+		assert_ok!(Vaccine::transfer_get_vaccine_right(
+			Origin::signed(DAVE),
+			role_id(DAVE),
+			role_id(FRANK),
+			vac_id(1)
+		));
+		assert_eq!(Vaccine::vaccines(vac_id(1)).unwrap().status, VacStatus::Used);
+		assert_eq!(Vaccine::held_vials(role_id(DAVE), vac_id(1)), None);
+		assert_noop!(
+			Vaccine::transfer_get_vaccine_right(
+				Origin::signed(DAVE),
+				role_id(DAVE),
+				role_id(GEORGE),
+				vac_id(1)
+			),
+			Error::<Test>::ExceedMaxShotNumber
+		);
+		assert_ok!(Vaccine::confirm_vaccine(
+			Origin::signed(FRANK),
+			role_id(FRANK),
+			role_id(DAVE),
+			vac_id(1)
+		));
+	});
+}
+
+#[test]
+fn should_migrate_baseline_storage_to_storage_version_1() {
+	use crate::{
+		migrations::v1::{OldMovingInfo, OldPassportInfo, OldVaccineInfo},
+		OwnershipTracking, VacStatus, VaccineType, Vaccines,
+	};
+	use codec::Encode;
+	use frame_support::{
+		migration::put_storage_value, storage::unhashed, traits::StorageVersion, Blake2_128Concat,
+		StorageHasher,
+	};
+	use pallet_account::migrations::v1::role_id_of;
+
+	ExtBuilder::default().build().execute_with(|| {
+		// the baseline layout keyed by legacy role IDs
+		let (bob, charlie, dave, frank) =
+			(b"bob".to_vec(), b"charlie".to_vec(), b"dave".to_vec(), b"frank".to_vec());
+		let history = [
+			VacStatus::Manufactured,
+			VacStatus::Shipped,
+			VacStatus::Received,
+			VacStatus::Usable,
+			VacStatus::Used,
+		];
+		// (vaccine, doses handed out, length of its history)
+		for (n, inoculation_count, steps) in [(1, 1, 5), (2, 8, 5), (3, 0, 2), (4, 0, 0)] {
+			let old = OldVaccineInfo {
+				vac_id: Some(vac_id(n)),
+				manufacture_id: Some(bob.clone()),
+				owner_id: Some(dave.clone()),
+				buyer_id: Some(dave.clone()),
+				vao_list: vec![charlie.clone()],
+				buy_confirm: true,
+				vac_type_id: Some(VacType::COVID19),
+				max_inoculations_number: 8,
+				inoculation_count,
+			};
+			unhashed::put(&Vaccines::<Test>::hashed_key_for(vac_id(n)), &old);
+			let trackings = history[..steps]
+				.iter()
+				.map(|status| OldMovingInfo {
+					vac_id: vac_id(n),
+					from: Some(bob.clone()),
+					to: Some(dave.clone()),
+					time: Some(1_000),
+					status: Some(status.clone()),
+				})
+				.collect::<Vec<_>>();
+			unhashed::put(&OwnershipTracking::<Test>::hashed_key_for(vac_id(n)), &trackings);
+		}
+		let passport = OldPassportInfo {
+			user_id: frank.clone(),
+			vac_list: vec![vac_id(1)],
+			inoculation_count: 1,
+		};
+		let frank_key = Blake2_128Concat::hash(&frank.encode());
+		put_storage_value(b"Vaccine", b"VaccinePassports", &frank_key, passport);
+		let used_key = [Blake2_128Concat::hash(&vac_id(1).encode()), frank_key.clone()].concat();
+		put_storage_value(b"Vaccine", b"UsedVaccine", &used_key, true);
+		VaccineType::<Test>::put(vec![VacType::COVID19, VacType::FLU]);
+		StorageVersion::new(0).put::<Vaccine>();
+
+		crate::migrations::v1::migrate::<Test>();
+
+		assert_eq!(StorageVersion::get::<Vaccine>(), StorageVersion::new(1));
+		let vac_info = Vaccine::vaccines(vac_id(1)).unwrap();
+		assert_eq!(vac_info.manufacture_id, Some(role_id_of(&bob)));
+		assert_eq!(vac_info.owner_id, Some(role_id_of(&dave)));
+		assert_eq!(vac_info.vao_list.into_inner(), vec![role_id_of(&charlie)]);
+		assert_eq!(vac_info.lot, None);
+		assert_eq!(vac_info.expires_at, None);
+		// the status follows the history, doses are left in the first vial
+		let status = |n| Vaccine::vaccines(vac_id(n)).unwrap().status;
+		assert_eq!(status(1), VacStatus::Usable);
+		assert_eq!(status(2), VacStatus::Used);
+		assert_eq!(status(3), VacStatus::Shipped);
+		assert_eq!(status(4), VacStatus::Manufactured);
+		let tracking = Vaccine::ownership_tracking(vac_id(1)).pop().unwrap();
+		assert_eq!(tracking.to, Some(role_id_of(&dave)));
+		assert_eq!(tracking.time, Some(1_000));
+		assert_eq!(tracking.delegation, None);
+
+		let passport = Vaccine::vaccine_passports(role_id_of(&frank)).unwrap();
+		assert_eq!(passport.user_id, role_id_of(&frank));
+		assert_eq!(passport.vac_list.into_inner(), vec![vac_id(1)]);
+		assert!(passport.recalled.is_empty());
+		assert!(Vaccine::used_vaccine(vac_id(1), role_id_of(&frank)));
+		// the doses every vial was registered with
+		assert_eq!(Vaccine::doses_per_vial(VacType::COVID19), Some(8));
+		assert_eq!(Vaccine::doses_per_vial(VacType::FLU), Some(8));
+		assert_eq!(Vaccine::doses_per_vial(VacType::HPV), None);
+	});
+}
+
+// accounts set up in `MockAccounts` rather than through the account pallet
+mod with_mock_accounts {
+	use super::{vac_id, ALICE, BOB, DAVE, DOSES, EXPIRES_AT};
+	use crate::{mock::role_id, mock_accounts::*, VacType};
+	use frame_support::{assert_noop, assert_ok};
+	use pallet_account::{Capability, Role, RoleStatus};
+	use sp_runtime::DispatchError;
+
+	const WAREHOUSE: u64 = 8;
+
+	#[test]
+	fn should_follow_the_accounts_of_the_account_pallet() {
+		new_test_ext().execute_with(|| {
+			Timestamp::set_timestamp(1_000);
+			MockAccounts::set_role(&role_id(ALICE), ALICE, Role::SYSMAN, RoleStatus::Approved);
+			MockAccounts::set_role(&role_id(BOB), BOB, Role::VM, RoleStatus::Approved);
+			MockAccounts::set_role(&role_id(DAVE), DAVE, Role::VAD, RoleStatus::Approved);
+			MockAccounts::add_sub_account(
+				&role_id(DAVE),
+				&role_id(WAREHOUSE),
+				WAREHOUSE,
+				vec![Capability::ReceiveVaccine],
+			);
+
+			assert_ok!(Vaccine::register_vac_type(
+				Origin::signed(ALICE),
+				role_id(ALICE),
+				VacType::COVID19,
+				DOSES
+			));
+			assert_ok!(Vaccine::register_vac_info(
+				Origin::signed(BOB),
+				role_id(BOB),
+				vac_id(1),
+				VacType::COVID19,
+				EXPIRES_AT
+			));
+			assert_ok!(Vaccine::transfer_vaccine(
+				Origin::signed(BOB),
+				role_id(BOB),
+				role_id(WAREHOUSE),
+				vac_id(1)
+			));
+			assert_ok!(Vaccine::receive_vaccine(
+				Origin::signed(WAREHOUSE),
+				role_id(WAREHOUSE),
+				role_id(BOB),
+				vac_id(1)
+			));
+			let tracking = Vaccine::ownership_tracking(vac_id(1)).pop().unwrap();
+			assert_eq!(tracking.to_org, Some(role_id(DAVE)));
+
+			// a revoked manufacture registers no more vials
+			MockAccounts::set_role(&role_id(BOB), BOB, Role::VM, RoleStatus::Revoked);
+			assert_noop!(
+				Vaccine::register_vac_info(
+					Origin::signed(BOB),
+					role_id(BOB),
+					vac_id(2),
+					VacType::COVID19,
+					EXPIRES_AT
+				),
+				DispatchError::Other("AlreadyRevoked")
+			);
+		});
+	}
+}
@@ -0,0 +1,82 @@
+//! Transaction payment waiving the fees of approved health actors.
+
+use crate::{Account, AccountId, Balance, Call, Runtime};
+use codec::{Decode, Encode};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, SignedExtension},
+	transaction_validity::{TransactionValidity, TransactionValidityError, ValidTransaction},
+	DispatchResult, RuntimeDebug,
+};
+
+type Inner = ChargeTransactionPayment<Runtime>;
+
+/// Charges the fee like `ChargeTransactionPayment`, except for calls of the account and vaccine
+/// pallets signed by the key of an approved role. Those are free up to `FreeTransactions` per
+/// `FreeTransactionPeriod` of each key, so the exemption can not be used to spam the chain.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo, RuntimeDebug)]
+pub struct ChargeOrWaiveFee(Inner);
+
+impl From<Balance> for ChargeOrWaiveFee {
+	/// Charge the fee with an additional `tip` when the transaction is not free.
+	fn from(tip: Balance) -> Self {
+		Self(Inner::from(tip))
+	}
+}
+
+impl ChargeOrWaiveFee {
+	fn is_exempt_call(call: &Call) -> bool {
+		matches!(call, Call::Account(..) | Call::Vaccine(..))
+	}
+}
+
+impl SignedExtension for ChargeOrWaiveFee {
+	// encoded like the extension it wraps, wallets keep working unchanged
+	const IDENTIFIER: &'static str = Inner::IDENTIFIER;
+	type AccountId = AccountId;
+	type Call = Call;
+	type AdditionalSigned = ();
+	// `None` for a fee-free transaction
+	type Pre = Option<<Inner as SignedExtension>::Pre>;
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		if Self::is_exempt_call(call) && Account::can_send_free(who) {
+			return Ok(ValidTransaction::default())
+		}
+		self.0.validate(who, call, info, len)
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		if Self::is_exempt_call(call) && Account::use_free_transaction(who) {
+			return Ok(None)
+		}
+		self.0.pre_dispatch(who, call, info, len).map(Some)
+	}
+
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		Inner::post_dispatch(pre.flatten(), info, post_info, len, result)
+	}
+}
@@ -47,6 +47,12 @@ pub use sp_runtime::{Perbill, Permill};
 pub use pallet_account;
 pub use pallet_vaccine;
 
+mod free_transactions;
+pub use free_transactions::ChargeOrWaiveFee;
+
+#[cfg(test)]
+mod tests;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -102,10 +108,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -267,6 +273,28 @@ impl pallet_account::Config for Runtime {
 	type Event = Event;
 	type MaxListSize = ConstU32<30>;
 	type UnixTime = pallet_timestamp::Pallet<Self>;
+	// sweep expired role approvals once an hour
+	type ExpiryPeriod = ConstU64<{ 60 * 60 * 1000 }>;
+	// proposals of system managers stay open for a week
+	type ProposalLifetime = ConstU64<{ 7 * 24 * 60 * 60 * 1000 }>;
+	type MaxProfileLength = ConstU32<128>;
+	type MaxDocuments = ConstU32<8>;
+	// a lost key is recovered after two days unless cancelled
+	type RecoveryDelay = ConstU64<{ 2 * 24 * 60 * 60 * 1000 }>;
+	// operator keys are lent for a shift of up to half a day
+	type MaxDelegationPeriod = ConstU64<{ 12 * 60 * 60 * 1000 }>;
+	// passports are erased together with user accounts
+	type OnErasure = Vaccine;
+	// a sub-account is not removed while it holds vials
+	type OnSubAccountRemoval = Vaccine;
+	type Currency = Balances;
+	// bond an organisation puts up for each of its roles, slashed on proven misconduct
+	type RoleBond = ConstU128<1_000_000_000_000>;
+	// approved health actors send up to 100 fee-free transactions an hour
+	type FreeTransactions = ConstU32<100>;
+	type FreeTransactionPeriod = ConstU32<HOURS>;
+	type MaxDidEntries = ConstU32<8>;
+	type MaxDidLength = ConstU32<256>;
 }
 
 /// Configure the pallet-template in pallets/template.
@@ -275,6 +303,14 @@ impl pallet_vaccine::Config for Runtime {
 	type MaxListSize = ConstU32<30>;
 	type UnixTime = pallet_timestamp::Pallet<Self>;
 	type AccountInfo = Account;
+	// each role ID makes up to 100 vaccine calls in ten minutes unless configured otherwise
+	type QuotaWindow = ConstU32<{ 10 * MINUTES }>;
+	type DefaultQuota = ConstU32<100>;
+	// every vial of a lot is written in the block registering it
+	type MaxLotSize = ConstU32<2_000>;
+	type MaxDosesPerVial = ConstU32<20>;
+	// a batch of a lot recall stays well within a block
+	type MaxRecallBatch = ConstU32<200>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -313,7 +349,8 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	ChargeOrWaiveFee,
+	pallet_vaccine::CheckVaccineQuota<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -474,6 +511,29 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_account_runtime_api::AccountDidApi<Block, AccountId> for Runtime {
+		fn resolve_did(
+			role_id: pallet_account::RoleId,
+		) -> Option<pallet_account::DidResolution<AccountId>> {
+			Account::resolve_did(&role_id)
+		}
+	}
+
+	impl pallet_vaccine_runtime_api::VaccineQuotaApi<Block, BlockNumber> for Runtime {
+		fn quota_usage(role_id: pallet_account::RoleId) -> pallet_vaccine::QuotaUsage<BlockNumber> {
+			Vaccine::quota_usage(&role_id)
+		}
+	}
+
+	impl pallet_vaccine_runtime_api::VaccineExpiryApi<Block> for Runtime {
+		fn expiring_vials(
+			holder: pallet_account::RoleId,
+			within: u64,
+		) -> Vec<(pallet_vaccine::VacId, u64)> {
+			Vaccine::expiring_vials(&holder, within)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -0,0 +1,38 @@
+//! Checks that the heaviest vaccine calls allowed by the configuration fit in a block.
+
+use crate::{BlockWeights, Call, Runtime};
+use frame_support::{
+	traits::Get,
+	weights::{DispatchClass, GetDispatchInfo, Weight},
+};
+use pallet_account::RoleId;
+use pallet_vaccine::{RecallTarget, VacType};
+
+fn max_extrinsic() -> Weight {
+	let weights = BlockWeights::get();
+	let max_extrinsic = weights.get(DispatchClass::Normal).max_extrinsic.unwrap();
+	assert!(max_extrinsic <= weights.max_block);
+	max_extrinsic
+}
+
+#[test]
+fn largest_lot_registration_fits_in_a_block() {
+	let register = Call::Vaccine(pallet_vaccine::Call::register_lot {
+		manufacture: RoleId::default(),
+		lot_number: b"LOT1".to_vec(),
+		manufactured_at: 0,
+		quantity: <Runtime as pallet_vaccine::Config>::MaxLotSize::get(),
+		vac_type: VacType::COVID19,
+		expires_at: 0,
+	});
+	assert!(register.get_dispatch_info().weight <= max_extrinsic());
+}
+
+#[test]
+fn lot_recall_fits_in_a_block() {
+	let recall = Call::Vaccine(pallet_vaccine::Call::recall {
+		recaller: RoleId::default(),
+		target: RecallTarget::Lot(b"LOT1".to_vec()),
+	});
+	assert!(recall.get_dispatch_info().weight <= max_extrinsic());
+}